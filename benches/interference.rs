@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+#[path = "../src/geometry.rs"]
+mod geometry;
+
+use geometry::{broadphase, circle_intersection};
+
+const ARENA: Vec2 = Vec2::new(1920.0, 1080.0);
+const MAX_RADIUS: f32 = 400.0;
+const COUNTS: [usize; 3] = [10, 100, 1000];
+
+// Stands in for a wave: where its origin is and how far its front has reached.
+#[derive(Component)]
+struct Front {
+    position: Vec2,
+    radius: f32,
+}
+
+fn spawn_fronts(count: usize) -> World {
+    let mut rng = fastrand::Rng::with_seed(0);
+    let mut world = World::new();
+    for _ in 0..count {
+        world.spawn(Front {
+            position: Vec2::new(rng.f32(), rng.f32()) * ARENA - ARENA / 2.0,
+            radius: rng.f32() * MAX_RADIUS,
+        });
+    }
+    world
+}
+
+fn narrowphase(front1: &Front, front2: &Front) -> bool {
+    circle_intersection(
+        front1.position,
+        front1.radius,
+        front2.position,
+        front2.radius,
+    )
+    .is_some()
+}
+
+// What interference detection did before the broadphase: every pair of waves.
+fn all_pairs(world: &World, query: &mut QueryState<&Front>) -> usize {
+    query
+        .iter_combinations::<2>(world)
+        .filter(|[front1, front2]| narrowphase(front1, front2))
+        .count()
+}
+
+fn sweep_and_prune(
+    world: &World,
+    query: &mut QueryState<(Entity, &Front)>,
+    bounds: &mut Vec<(Entity, Vec2, f32)>,
+    pairs: &mut Vec<(Entity, Entity)>,
+) -> usize {
+    bounds.clear();
+    bounds.extend(
+        query
+            .iter(world)
+            .map(|(entity, front)| (entity, front.position, front.radius)),
+    );
+    broadphase(bounds, pairs);
+
+    pairs
+        .iter()
+        .filter(
+            |(entity1, entity2)| match query.get_many(world, [*entity1, *entity2]) {
+                Ok([(_, front1), (_, front2)]) => narrowphase(front1, front2),
+                Err(_) => false,
+            },
+        )
+        .count()
+}
+
+fn interference(c: &mut Criterion) {
+    let mut group = c.benchmark_group("interference");
+
+    for count in COUNTS {
+        let mut world = spawn_fronts(count);
+        let mut all_query = world.query::<&Front>();
+        let mut sweep_query = world.query::<(Entity, &Front)>();
+        let mut bounds = Vec::new();
+        let mut pairs = Vec::new();
+
+        assert_eq!(
+            all_pairs(&world, &mut all_query),
+            sweep_and_prune(&world, &mut sweep_query, &mut bounds, &mut pairs),
+            "broadphase missed intersecting pairs with {count} waves",
+        );
+
+        group.bench_with_input(BenchmarkId::new("all_pairs", count), &world, |b, world| {
+            b.iter(|| all_pairs(black_box(world), &mut all_query))
+        });
+        group.bench_with_input(BenchmarkId::new("broadphase", count), &world, |b, world| {
+            b.iter(|| sweep_and_prune(black_box(world), &mut sweep_query, &mut bounds, &mut pairs))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, interference);
+criterion_main!(benches);
@@ -0,0 +1,298 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    ecs::system::SystemParam,
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    boss::{Boss, Phase},
+    enemy::{Enemy, EnemyHitbox, Hitstun, PulsingEnemy, ShootingEnemy},
+    force::{Knockback, WaveSusceptible},
+    health::{Health, HealthBar},
+    pattern::Pattern,
+    projectile::{Projectile, ProjectileBehaviour},
+    steering::{Steering, Weighted},
+    wave::{WaveKind, WaveRider},
+};
+
+// Folders can't be listed on wasm, so archetypes have to be named for it too.
+const ENEMIES: [&str; 4] = ["normie", "layer", "ranger", "pulser"];
+const BOSSES: [&str; 1] = ["conductor"];
+
+// Everything needed to spawn one kind of enemy, loaded from
+// `assets/enemies/<name>.enemy.ron`.
+#[derive(Deserialize, TypeUuid, Clone)]
+#[uuid = "5c0f8a3e-7d2b-4c61-9a8e-2f4b6d1e3a70"]
+pub struct EnemyArchetype {
+    pub color: Color,
+    #[serde(default)]
+    pub texture: Option<String>,
+    pub size: f32,
+    pub health: f32,
+    // Damage per collision with anything enemies target.
+    pub contact_damage: f32,
+    pub susceptibility: WaveSusceptible,
+    pub movement: Movement,
+    #[serde(default)]
+    pub shooting: Option<Shooting>,
+    #[serde(default)]
+    pub pulsing: Option<Pulsing>,
+    #[serde(default)]
+    pub cost: Cost,
+    // Only bosses, loaded from `assets/bosses`, have phases.
+    #[serde(default)]
+    pub phases: Vec<Phase>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub enum Body {
+    // Pushed around by the player and other enemies.
+    Dynamic,
+    // Only ever moves at the velocity it is given.
+    Kinematic,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Movement {
+    pub speed: f32,
+    pub body: Body,
+    #[serde(default)]
+    pub rider: Option<WaveRider>,
+    // Archetypes without steering head straight for their target.
+    #[serde(default)]
+    pub steering: Vec<Weighted>,
+}
+
+// Projectiles fired in `pattern` every `interval` seconds. Straight and at
+// constant speed unless given a `behaviour`.
+#[derive(Deserialize, Clone)]
+pub struct Shooting {
+    pub interval: f32,
+    pub pattern: Pattern,
+    pub speed: f32,
+    pub lifespan: f32,
+    pub damage: f32,
+    pub size: f32,
+    pub texture: String,
+    #[serde(default)]
+    pub behaviour: ProjectileBehaviour,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Pulsing {
+    pub interval: f32,
+    pub kind: WaveKind,
+    pub profile: String,
+}
+
+// What the director pays for an enemy. It only spawns once the round budget
+// exceeds `required_budget`, and waits `delay` seconds before the next spawn.
+#[derive(Deserialize, Clone, Copy, Default)]
+pub struct Cost {
+    pub budget: u32,
+    pub delay: f32,
+    pub required_budget: u32,
+}
+
+impl EnemyArchetype {
+    pub fn spawn(&self, cmd: &mut Commands, pos: Vec3, assets: &AssetServer) {
+        let mut sprite = SpriteBundle {
+            sprite: Sprite {
+                color: self.color,
+                custom_size: Some(Vec2::splat(self.size)),
+                ..default()
+            },
+            transform: Transform::from_translation(pos),
+            ..default()
+        };
+        if let Some(texture) = &self.texture {
+            sprite.texture = assets.load(texture.as_str());
+        }
+
+        let mut enemy = cmd.spawn((
+            sprite,
+            Enemy {
+                speed: self.movement.speed,
+            },
+            Collider::cuboid(self.size / 2.0, self.size / 2.0),
+            Health::new(self.health),
+            Hitstun::new(0.0),
+            Velocity::default(),
+            Knockback::default(),
+            self.susceptibility,
+            EnemyHitbox {
+                damage: self.contact_damage,
+                once: false,
+            },
+        ));
+
+        match self.movement.body {
+            Body::Dynamic => enemy.insert((RigidBody::Dynamic, LockedAxes::ROTATION_LOCKED)),
+            // Interference sensors have no body, so kinematic enemies only touch
+            // them with kinematic-static contacts enabled.
+            Body::Kinematic => enemy.insert((
+                RigidBody::KinematicVelocityBased,
+                ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_STATIC,
+            )),
+        };
+        if let Some(rider) = self.movement.rider {
+            enemy.insert(rider);
+        }
+        if !self.movement.steering.is_empty() {
+            enemy.insert(Steering(self.movement.steering.clone()));
+        }
+        if let Some(shooting) = &self.shooting {
+            enemy.insert(ShootingEnemy {
+                timer: Timer::from_seconds(shooting.interval, TimerMode::Repeating),
+                pattern: shooting.pattern.clone(),
+                volley: 0,
+                queued: Vec::new(),
+                projectile: Projectile {
+                    speed: shooting.speed,
+                    lifespan: shooting.lifespan,
+                    damage: shooting.damage,
+                    size: shooting.size,
+                    texture: assets.load(shooting.texture.as_str()),
+                    behaviour: shooting.behaviour.clone(),
+                },
+            });
+        }
+        if let Some(pulsing) = &self.pulsing {
+            enemy.insert(PulsingEnemy {
+                timer: Timer::from_seconds(pulsing.interval, TimerMode::Repeating),
+                kind: pulsing.kind,
+                profile: pulsing.profile.clone(),
+            });
+        }
+
+        if !self.phases.is_empty() {
+            enemy.insert(Boss::new(self.phases.clone()));
+        }
+
+        let size = self.size;
+        enemy.with_children(|parent| {
+            parent.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::DARK_GREEN,
+                        custom_size: Some(Vec2::new(size, 5.0)),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(0.0, size / 2.0 + 10.0, 0.1),
+                    ..default()
+                },
+                HealthBar::new(size),
+            ));
+            parent.spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::RED,
+                    custom_size: Some(Vec2::new(size, 5.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, size / 2.0 + 10.0, 0.05),
+                ..default()
+            });
+        });
+    }
+}
+
+#[derive(Default)]
+struct EnemyArchetypeLoader;
+
+impl AssetLoader for EnemyArchetypeLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let archetype: EnemyArchetype = ron::de::from_bytes(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(archetype));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["enemy.ron"]
+    }
+}
+
+#[derive(Resource, Default)]
+struct ArchetypeHandles {
+    enemies: Vec<Handle<EnemyArchetype>>,
+    bosses: Vec<Handle<EnemyArchetype>>,
+}
+
+#[derive(SystemParam)]
+pub struct EnemyArchetypes<'w> {
+    handles: Res<'w, ArchetypeHandles>,
+    assets: Res<'w, Assets<EnemyArchetype>>,
+}
+
+impl EnemyArchetypes<'_> {
+    // Picks one of the loaded archetypes the director can currently pay for.
+    pub fn choose(&self, budget: u32, remaining: u32) -> Option<&EnemyArchetype> {
+        let affordable: Vec<_> = self
+            .handles
+            .enemies
+            .iter()
+            .filter_map(|handle| self.assets.get(handle))
+            .filter(|archetype| {
+                budget > archetype.cost.required_budget && remaining >= archetype.cost.budget
+            })
+            .collect();
+
+        if affordable.is_empty() {
+            return None;
+        }
+        Some(affordable[fastrand::usize(..affordable.len())])
+    }
+
+    pub fn choose_boss(&self) -> Option<&EnemyArchetype> {
+        let bosses: Vec<_> = self
+            .handles
+            .bosses
+            .iter()
+            .filter_map(|handle| self.assets.get(handle))
+            .collect();
+
+        if bosses.is_empty() {
+            return None;
+        }
+        Some(bosses[fastrand::usize(..bosses.len())])
+    }
+}
+
+pub struct Plugin;
+
+impl Plugin {
+    fn load_archetypes(mut handles: ResMut<ArchetypeHandles>, assets: Res<AssetServer>) {
+        handles.enemies = load_all(&assets, "enemies", &ENEMIES);
+        handles.bosses = load_all(&assets, "bosses", &BOSSES);
+    }
+}
+
+// Loads every archetype in `assets/<folder>`, or only the named ones where the
+// folder can't be listed.
+fn load_all(assets: &AssetServer, folder: &str, names: &[&str]) -> Vec<Handle<EnemyArchetype>> {
+    if let Ok(handles) = assets.load_folder(folder) {
+        return handles.into_iter().map(|handle| handle.typed()).collect();
+    }
+    names
+        .iter()
+        .map(|name| assets.load(format!("{folder}/{name}.enemy.ron")))
+        .collect()
+}
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<EnemyArchetype>()
+            .init_asset_loader::<EnemyArchetypeLoader>()
+            .init_resource::<ArchetypeHandles>()
+            .add_startup_system(Self::load_archetypes);
+    }
+}
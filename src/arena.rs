@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    wave::{Medium, Reflector},
+    GameState, MainCamera,
+};
+
+#[derive(Component)]
+pub struct ArenaEntity;
+
+const OBSTACLES: [(Vec2, Vec2); 2] = [
+    (Vec2::new(-250.0, 0.0), Vec2::new(20.0, 80.0)),
+    (Vec2::new(250.0, 0.0), Vec2::new(20.0, 80.0)),
+];
+
+const MEDIA: [(Vec2, Vec2, f32); 2] = [
+    (Vec2::new(0.0, 220.0), Vec2::new(160.0, 50.0), 0.5),
+    (Vec2::new(0.0, -220.0), Vec2::new(160.0, 50.0), 0.5),
+];
+
+pub struct Plugin;
+
+impl Plugin {
+    fn spawn_arena(mut cmd: Commands, q_camera: Query<&Camera, With<MainCamera>>) {
+        let Some(viewport_size) = q_camera.single().logical_viewport_size() else { return };
+
+        cmd.spawn((
+            SpatialBundle::default(),
+            Reflector {
+                half_extents: viewport_size / 2.0,
+            },
+            ArenaEntity,
+        ));
+
+        for (position, half_extents) in OBSTACLES {
+            cmd.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::hex("3a3a3a").unwrap(),
+                        custom_size: Some(half_extents * 2.0),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(position.extend(0.0)),
+                    ..default()
+                },
+                Reflector { half_extents },
+                Collider::cuboid(half_extents.x, half_extents.y),
+                RigidBody::Fixed,
+                ArenaEntity,
+            ));
+        }
+
+        for (position, half_extents, speed_factor) in MEDIA {
+            cmd.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgba(0.4, 0.3, 0.6, 0.15),
+                        custom_size: Some(half_extents * 2.0),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(position.extend(-0.1)),
+                    ..default()
+                },
+                Medium {
+                    half_extents,
+                    speed_factor,
+                },
+                ArenaEntity,
+            ));
+        }
+    }
+
+    fn cleanup(mut cmd: Commands, q_arena: Query<Entity, With<ArenaEntity>>) {
+        for entity in &q_arena {
+            cmd.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(Self::spawn_arena.in_schedule(OnEnter(GameState::InGame)))
+            .add_system(Self::cleanup.in_schedule(OnExit(GameState::InGame)));
+    }
+}
@@ -0,0 +1,267 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_prototype_lyon::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    enemy::{Enemy, ShootingEnemy},
+    health::{Health, Invulnerable},
+    pattern::Pattern,
+    projectile::ProjectileBehaviour,
+    utils::PlaySound,
+    wave::{InterferenceHitEvent, InterferenceKind, WaveKind},
+    GameState,
+};
+
+const BAR_COLOR: Color = Color::ORANGE_RED;
+const SHIELDED_BAR_COLOR: Color = Color::GRAY;
+
+// One stage of a boss fight, entered once the boss's health fraction drops to
+// `threshold`. Anything left out carries over from the previous phase.
+#[derive(Deserialize, Clone)]
+pub struct Phase {
+    pub threshold: f32,
+    #[serde(default)]
+    pub speed: Option<f32>,
+    #[serde(default)]
+    pub interval: Option<f32>,
+    #[serde(default)]
+    pub pattern: Option<Pattern>,
+    #[serde(default)]
+    pub projectile: Option<ProjectileBehaviour>,
+    #[serde(default)]
+    pub shield: Option<ShieldDefinition>,
+}
+
+// Makes the boss ignore damage until it has been hit by `hits` interferences
+// of `kind`.
+#[derive(Deserialize, Clone, Copy)]
+pub struct ShieldDefinition {
+    pub kind: InterferenceKind,
+    pub hits: u32,
+}
+
+#[derive(Component)]
+pub struct Boss {
+    phases: Vec<Phase>,
+    entered: usize,
+}
+
+impl Boss {
+    pub fn new(phases: Vec<Phase>) -> Self {
+        Boss { phases, entered: 0 }
+    }
+}
+
+#[derive(Component)]
+pub struct Shield {
+    kind: InterferenceKind,
+    remaining: u32,
+}
+
+#[derive(Component)]
+struct ShieldRing;
+
+#[derive(Component)]
+struct BossBar;
+
+#[derive(Component)]
+struct BossBarFill;
+
+#[derive(SystemSet, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Plugin;
+
+impl Plugin {
+    fn advance_phases(
+        mut cmd: Commands,
+        mut q_boss: Query<(
+            Entity,
+            &mut Boss,
+            &Health,
+            &mut Enemy,
+            Option<&mut ShootingEnemy>,
+        )>,
+        mut ev_sound: EventWriter<PlaySound>,
+    ) {
+        for (entity, mut boss, health, mut enemy, mut shooter) in &mut q_boss {
+            while boss.entered < boss.phases.len()
+                && health.percentage() <= boss.phases[boss.entered].threshold
+            {
+                let phase = boss.phases[boss.entered].clone();
+                boss.entered += 1;
+
+                if let Some(speed) = phase.speed {
+                    enemy.speed = speed;
+                }
+                if let Some(shooter) = shooter.as_mut() {
+                    if let Some(interval) = phase.interval {
+                        shooter
+                            .timer
+                            .set_duration(Duration::from_secs_f32(interval));
+                        shooter.timer.reset();
+                    }
+                    if let Some(pattern) = phase.pattern {
+                        shooter.pattern = pattern;
+                        shooter.volley = 0;
+                        shooter.queued.clear();
+                    }
+                    if let Some(behaviour) = phase.projectile {
+                        shooter.projectile.behaviour = behaviour;
+                    }
+                }
+                if let Some(shield) = phase.shield {
+                    cmd.entity(entity)
+                        .insert((
+                            Shield {
+                                kind: shield.kind,
+                                remaining: shield.hits,
+                            },
+                            Invulnerable,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                ShapeBundle {
+                                    path: GeometryBuilder::build_as(&shapes::Circle {
+                                        radius: 70.0,
+                                        center: Vec2::ZERO,
+                                    }),
+                                    transform: Transform::from_xyz(0.0, 0.0, 0.2),
+                                    ..default()
+                                },
+                                Stroke::new(shield_color(shield.kind), 4.0),
+                                ShieldRing,
+                            ));
+                        });
+                }
+                ev_sound.send(PlaySound("dong.ogg".to_owned()));
+            }
+        }
+    }
+
+    fn strip_shields(
+        mut cmd: Commands,
+        mut q_shield: Query<(&mut Shield, &Children)>,
+        q_ring: Query<(), With<ShieldRing>>,
+        mut ev_hit: EventReader<InterferenceHitEvent>,
+        mut ev_sound: EventWriter<PlaySound>,
+    ) {
+        for hit in &mut ev_hit {
+            let Ok((mut shield, children)) = q_shield.get_mut(hit.target()) else { continue };
+            if hit.kind() != shield.kind || shield.remaining == 0 {
+                continue;
+            }
+
+            shield.remaining -= 1;
+            if shield.remaining > 0 {
+                continue;
+            }
+
+            cmd.entity(hit.target()).remove::<(Shield, Invulnerable)>();
+            for child in children.iter() {
+                if q_ring.contains(*child) {
+                    cmd.entity(*child).despawn_recursive();
+                }
+            }
+            ev_sound.send(PlaySound("ding.ogg".to_owned()));
+        }
+    }
+
+    fn spawn_bar(
+        mut cmd: Commands,
+        q_boss: Query<(), Added<Boss>>,
+        q_bar: Query<(), With<BossBar>>,
+    ) {
+        if q_boss.is_empty() || !q_bar.is_empty() {
+            return;
+        }
+
+        cmd.spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        left: Val::Percent(20.0),
+                        bottom: Val::Px(30.0),
+                        ..default()
+                    },
+                    size: Size::new(Val::Percent(60.0), Val::Px(16.0)),
+                    ..default()
+                },
+                background_color: Color::RED.into(),
+                ..default()
+            },
+            BossBar,
+        ))
+        .with_children(|bar| {
+            bar.spawn((
+                NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                        ..default()
+                    },
+                    background_color: BAR_COLOR.into(),
+                    ..default()
+                },
+                BossBarFill,
+            ));
+        });
+    }
+
+    fn update_bar(
+        mut cmd: Commands,
+        q_boss: Query<(&Health, Option<&Shield>), With<Boss>>,
+        q_bar: Query<Entity, With<BossBar>>,
+        mut q_fill: Query<(&mut Style, &mut BackgroundColor), With<BossBarFill>>,
+    ) {
+        let Ok((health, shield)) = q_boss.get_single() else {
+            for bar in &q_bar {
+                cmd.entity(bar).despawn_recursive();
+            }
+            return;
+        };
+
+        for (mut style, mut color) in &mut q_fill {
+            style.size.width = Val::Percent(100.0 * health.percentage().max(0.0));
+            *color = match shield {
+                Some(_) => SHIELDED_BAR_COLOR,
+                None => BAR_COLOR,
+            }
+            .into();
+        }
+    }
+
+    fn cleanup(mut cmd: Commands, q_bar: Query<Entity, With<BossBar>>) {
+        for bar in &q_bar {
+            cmd.entity(bar).despawn_recursive();
+        }
+    }
+}
+
+fn shield_color(kind: InterferenceKind) -> Color {
+    match kind {
+        InterferenceKind::Destructive => Color::WHITE,
+        InterferenceKind::Positive => WaveKind::Positive.color(),
+        InterferenceKind::Negative => WaveKind::Negative.color(),
+    }
+}
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (Self::advance_phases, Self::strip_shields)
+                .in_set(Self)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        )
+        .add_system(Self::spawn_bar.in_set(Self))
+        .add_system(Self::update_bar.after(Self::spawn_bar).in_set(Self))
+        .add_system(Self::cleanup.in_schedule(OnExit(GameState::InGame)))
+        .edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+            schedule.configure_set(
+                Self.after(crate::wave::Plugin)
+                    .run_if(in_state(GameState::InGame)),
+            );
+        });
+        app.configure_set(Self.run_if(in_state(GameState::InGame)));
+    }
+}
@@ -0,0 +1,252 @@
+use bevy::prelude::*;
+use bevy_prototype_lyon::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    enemy::EnemyTarget,
+    health::{Health, HealthBar},
+    profile::WaveProfiles,
+    utils::PlaySound,
+    wave::{DelayedWave, Faction, Wave, WaveBundle, WaveKind, WaveShape},
+    GameState,
+};
+
+const MAX_EMITTERS: usize = 3;
+const EMITTER_DURATION: f32 = 8.0;
+const EMITTER_PULSE_INTERVAL: f32 = 2.0;
+const EMITTER_HEALTH: f32 = 30.0;
+
+#[derive(Component)]
+pub struct EmitterLaunch {
+    target: Vec3,
+    flight_timer: Timer,
+}
+
+impl EmitterLaunch {
+    pub fn new(target: Vec3, flight_time: f32) -> Self {
+        EmitterLaunch {
+            target,
+            flight_timer: Timer::from_seconds(flight_time, TimerMode::Once),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Emitter {
+    pulse_timer: Timer,
+    lifetime: Timer,
+}
+
+#[derive(Component)]
+pub struct EmitterCountdown;
+
+#[derive(SystemSet, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Plugin;
+
+impl Plugin {
+    fn land_emitters(
+        mut cmd: Commands,
+        mut q_launch: Query<(Entity, &mut EmitterLaunch)>,
+        q_emitter: Query<(Entity, &Emitter)>,
+        mut ev_sound: EventWriter<PlaySound>,
+        profiles: WaveProfiles,
+        time: Res<FixedTime>,
+    ) {
+        let mut deployed: Vec<_> = q_emitter
+            .iter()
+            .map(|(entity, emitter)| (entity, emitter.lifetime.remaining_secs()))
+            .collect();
+
+        for (entity, mut launch) in &mut q_launch {
+            launch.flight_timer.tick(time.period);
+            if !launch.flight_timer.finished() {
+                continue;
+            }
+            cmd.entity(entity).despawn_recursive();
+
+            // Replace whichever deployed emitter would expire first.
+            if deployed.len() >= MAX_EMITTERS {
+                deployed.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+                if let Some((oldest, _)) = deployed.pop() {
+                    cmd.entity(oldest).despawn_recursive();
+                }
+            }
+
+            let transform = Transform::from_translation(launch.target);
+            pulse(&mut cmd, &profiles, transform);
+            ev_sound.send(PlaySound("ding.ogg".to_owned()));
+
+            let emitter = cmd
+                .spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::hex("bc53ff").unwrap(),
+                            custom_size: Some(Vec2::splat(20.0)),
+                            ..default()
+                        },
+                        transform,
+                        ..default()
+                    },
+                    Emitter {
+                        pulse_timer: Timer::from_seconds(
+                            EMITTER_PULSE_INTERVAL,
+                            TimerMode::Repeating,
+                        ),
+                        lifetime: Timer::from_seconds(EMITTER_DURATION, TimerMode::Once),
+                    },
+                    Health::new(EMITTER_HEALTH),
+                    EnemyTarget,
+                    Collider::ball(10.0),
+                    RigidBody::Fixed,
+                    ActiveEvents::COLLISION_EVENTS,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        SpriteBundle {
+                            sprite: Sprite {
+                                color: Color::DARK_GREEN,
+                                custom_size: Some(Vec2::new(30.0, 4.0)),
+                                ..default()
+                            },
+                            transform: Transform::from_xyz(0.0, 20.0, 0.1),
+                            ..default()
+                        },
+                        HealthBar::new(30.0),
+                    ));
+                    parent.spawn(SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::RED,
+                            custom_size: Some(Vec2::new(30.0, 4.0)),
+                            ..default()
+                        },
+                        transform: Transform::from_xyz(0.0, 20.0, 0.05),
+                        ..default()
+                    });
+                    parent.spawn((
+                        ShapeBundle::default(),
+                        Stroke::new(Color::hex("bc53ff").unwrap(), 3.0),
+                        EmitterCountdown,
+                    ));
+                })
+                .id();
+            deployed.push((emitter, EMITTER_DURATION));
+        }
+    }
+
+    fn update_emitters(
+        mut cmd: Commands,
+        mut q_emitter: Query<(Entity, &mut Emitter, &GlobalTransform)>,
+        profiles: WaveProfiles,
+        time: Res<FixedTime>,
+    ) {
+        for (entity, mut emitter, transform) in &mut q_emitter {
+            emitter.lifetime.tick(time.period);
+            if emitter.lifetime.finished() {
+                cmd.entity(entity).despawn_recursive();
+                continue;
+            }
+
+            emitter.pulse_timer.tick(time.period);
+            if emitter.pulse_timer.just_finished() {
+                pulse(&mut cmd, &profiles, transform.compute_transform());
+            }
+        }
+    }
+
+    fn update_countdown(
+        q_emitter: Query<(&Emitter, &Children)>,
+        mut q_countdown: Query<&mut Path, With<EmitterCountdown>>,
+    ) {
+        for (emitter, children) in &q_emitter {
+            for child in children.iter() {
+                let Ok(mut path) = q_countdown.get_mut(*child) else { continue };
+
+                let mut path_builder = PathBuilder::new();
+                path_builder.move_to(Vec2::Y * 16.0);
+
+                let fract = -(std::f32::consts::TAU / 32.0) * (1.0 - emitter.lifetime.percent());
+
+                for i in 1..=32 {
+                    path_builder.line_to(Vec2::new(
+                        f32::cos(i as f32 * fract + std::f32::consts::FRAC_PI_2) * 16.0,
+                        f32::sin(i as f32 * fract + std::f32::consts::FRAC_PI_2) * 16.0,
+                    ));
+                }
+                *path = path_builder.build();
+            }
+        }
+    }
+
+    fn recall_emitters(
+        mut cmd: Commands,
+        q_emitter: Query<Entity, Or<(With<Emitter>, With<EmitterLaunch>)>>,
+        keys: Res<Input<KeyCode>>,
+    ) {
+        if keys.just_pressed(KeyCode::R) {
+            for entity in &q_emitter {
+                cmd.entity(entity).despawn_recursive();
+            }
+        }
+    }
+
+    fn cleanup(
+        mut cmd: Commands,
+        q_emitter: Query<Entity, Or<(With<Emitter>, With<EmitterLaunch>)>>,
+    ) {
+        for entity in &q_emitter {
+            cmd.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn pulse(cmd: &mut Commands, profiles: &WaveProfiles, transform: Transform) {
+    let wave = profiles.wave(
+        "emitter",
+        WaveKind::Positive,
+        Faction::Player,
+        WaveShape::Ring,
+    );
+
+    cmd.spawn(DelayedWave::new(
+        Wave {
+            kind: WaveKind::Negative,
+            ..wave.clone()
+        },
+        transform,
+        0.5,
+    ));
+    let color = wave.color();
+    cmd.spawn((
+        WaveBundle {
+            shape_bundle: ShapeBundle {
+                path: wave.build_path(),
+                transform,
+                ..default()
+            },
+            wave,
+        },
+        Stroke::new(color, 2.0),
+    ));
+}
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            Self::land_emitters
+                .in_set(Self)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        )
+        .add_system(
+            Self::update_emitters
+                .in_set(Self)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        )
+        .add_system(Self::update_countdown.in_set(Self))
+        .add_system(Self::recall_emitters.in_set(Self))
+        .add_system(Self::cleanup.in_schedule(OnExit(GameState::InGame)))
+        .edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+            schedule.configure_set(Self.run_if(in_state(GameState::InGame)));
+        });
+        app.configure_set(Self.run_if(in_state(GameState::InGame)));
+    }
+}
@@ -1,12 +1,18 @@
 use std::time::Duration;
 
 use bevy::prelude::*;
+use bevy_prototype_lyon::prelude::*;
 use bevy_rapier2d::prelude::*;
 
 use crate::{
+    force::Knockback,
     health::HealthChangeEvent,
+    pattern::{Aim, Pattern, Shot},
     player::{AvgPlayerVel, Player},
-    utils::Lifespan,
+    profile::WaveProfiles,
+    projectile::Projectile,
+    steering::{Crowd, Steering},
+    wave::{Faction, Wave, WaveBundle, WaveKind, WaveRider, WaveShape},
     GameState,
 };
 
@@ -15,17 +21,29 @@ pub struct Enemy {
     pub speed: f32,
 }
 
+// Anything enemies move towards and their hitboxes damage.
+#[derive(Component)]
+pub struct EnemyTarget;
+
 #[derive(Component)]
 pub struct Hitstun(Timer);
 
+// Fires `pattern` every time `timer` finishes. Shots the pattern delays wait in
+// `queued` until they are due.
 #[derive(Component)]
 pub struct ShootingEnemy {
     pub timer: Timer,
-    pub speed: f32,
-    pub lifespan: f32,
-    pub damage: f32,
-    pub size: f32,
-    pub texture: Handle<Image>,
+    pub pattern: Pattern,
+    pub volley: u32,
+    pub queued: Vec<Shot>,
+    pub projectile: Projectile,
+}
+
+#[derive(Component)]
+pub struct PulsingEnemy {
+    pub timer: Timer,
+    pub kind: WaveKind,
+    pub profile: String,
 }
 
 #[derive(Component)]
@@ -58,22 +76,60 @@ pub struct Plugin;
 
 impl Plugin {
     fn enemy_movement(
-        q_player: Query<&GlobalTransform, With<Player>>,
-        mut q_enemy: Query<(&mut Velocity, &GlobalTransform, &Enemy, &mut Hitstun)>,
-        time: Res<Time>,
+        q_target: Query<(&GlobalTransform, Option<&Velocity>), (With<EnemyTarget>, Without<Enemy>)>,
+        mut q_enemy: Query<(
+            Entity,
+            &mut Velocity,
+            &GlobalTransform,
+            &Enemy,
+            &mut Hitstun,
+            Option<&WaveRider>,
+            Option<&Steering>,
+            &Knockback,
+        )>,
+        q_wave: Query<(&Wave, &GlobalTransform)>,
+        crowd: Res<Crowd>,
+        time: Res<FixedTime>,
     ) {
-        let Ok(player_transform) = q_player.get_single() else { return };
-        let player_pos = player_transform.translation().truncate();
-
-        for (mut enemy_vel, enemy_global_transform, enemy, mut hitstun) in &mut q_enemy {
+        for (
+            entity,
+            mut enemy_vel,
+            enemy_global_transform,
+            enemy,
+            mut hitstun,
+            rider,
+            steering,
+            knockback,
+        ) in &mut q_enemy
+        {
             if hitstun.is_set() {
-                hitstun.0.tick(time.delta());
+                hitstun.0.tick(time.period);
                 continue;
             }
             let enemy_pos = enemy_global_transform.translation().truncate();
-            let direction = player_pos - enemy_pos;
+            let nearest_target = q_target
+                .iter()
+                .map(|(transform, velocity)| {
+                    let velocity = velocity.map_or(Vec2::ZERO, |velocity| velocity.linvel);
+                    (transform.translation().truncate(), velocity)
+                })
+                .min_by(|(a, _), (b, _)| {
+                    a.distance_squared(enemy_pos)
+                        .total_cmp(&b.distance_squared(enemy_pos))
+                });
+            let Some((target_pos, target_vel)) = nearest_target else { return };
+            let direction = match steering {
+                Some(steering) => {
+                    steering.direction(entity, enemy_pos, target_pos, target_vel, &crowd)
+                }
+                None => (target_pos - enemy_pos).normalize_or_zero(),
+            };
 
-            enemy_vel.linvel = direction.normalize_or_zero() * enemy.speed;
+            let modifier = rider.map_or(1.0, |rider| {
+                rider.speed_modifier(enemy_pos, direction, q_wave.iter())
+            });
+
+            enemy_vel.linvel = direction * enemy.speed * modifier + knockback.0;
         }
     }
 
@@ -81,7 +137,7 @@ impl Plugin {
         mut cmd: Commands,
         q_player: Query<&GlobalTransform, With<Player>>,
         mut q_shooter: Query<(&GlobalTransform, &mut ShootingEnemy, &Hitstun), With<Enemy>>,
-        time: Res<Time>,
+        time: Res<FixedTime>,
         player_vel: Res<AvgPlayerVel>,
     ) {
         let Ok(player_transform) = q_player.get_single() else { return };
@@ -92,40 +148,74 @@ impl Plugin {
                 continue;
             }
 
-            shooter.timer.tick(time.delta());
+            shooter.timer.tick(time.period);
 
             if shooter.timer.finished() {
                 shooter.timer.reset();
 
-                let player_dist = transform.translation().truncate().distance(player_pos);
-                let travel_time = player_dist / shooter.speed;
-                let target_pos = player_pos + player_vel.0 * travel_time;
-                let target_dir =
-                    (target_pos - transform.translation().truncate()).normalize_or_zero();
+                let volley = shooter.pattern.volley(shooter.volley);
+                shooter.queued.extend(volley);
+                shooter.volley += 1;
+            }
 
+            let shooter_pos = transform.translation().truncate();
+            let mut queued = std::mem::take(&mut shooter.queued);
+            queued.retain_mut(|shot| {
+                shot.delay -= time.period.as_secs_f32();
+                if shot.delay > 0.0 {
+                    return true;
+                }
+
+                let aim_direction = match shot.aim {
+                    Aim::Predicted => {
+                        let travel_time =
+                            shooter_pos.distance(player_pos) / shooter.projectile.speed;
+                        player_pos + player_vel.0 * travel_time - shooter_pos
+                    }
+                    Aim::Direct => player_pos - shooter_pos,
+                    Aim::Fixed(angle) => Vec2::from_angle(angle),
+                };
+                let direction = shot.direction(aim_direction.normalize_or_zero());
+                shooter.projectile.spawn(&mut cmd, transform.translation(), direction);
+                false
+            });
+            shooter.queued = queued;
+        }
+    }
+
+    fn enemy_pulse(
+        mut cmd: Commands,
+        mut q_pulser: Query<
+            (&GlobalTransform, &Velocity, &mut PulsingEnemy, &Hitstun),
+            With<Enemy>,
+        >,
+        profiles: WaveProfiles,
+        time: Res<FixedTime>,
+    ) {
+        for (transform, velocity, mut pulser, hitstun) in &mut q_pulser {
+            if hitstun.is_set() {
+                continue;
+            }
+
+            pulser.timer.tick(time.period);
+
+            if pulser.timer.finished() {
+                pulser.timer.reset();
+
+                let mut wave =
+                    profiles.wave(&pulser.profile, pulser.kind, Faction::Enemy, WaveShape::Ring);
+                wave.source_velocity = velocity.linvel;
+                let color = wave.color();
                 cmd.spawn((
-                    SpriteBundle {
-                        texture: shooter.texture.clone(),
-                        transform: transform.compute_transform().with_rotation(
-                            Quat::from_rotation_z(-target_dir.angle_between(Vec2::NEG_Y)),
-                        ),
-                        ..default()
-                    },
-                    Collider::ball(shooter.size),
-                    Sensor,
-                    RigidBody::KinematicVelocityBased,
-                    ActiveEvents::COLLISION_EVENTS,
-                    ActiveCollisionTypes::KINEMATIC_STATIC
-                        | ActiveCollisionTypes::KINEMATIC_KINEMATIC,
-                    Velocity {
-                        angvel: 0.0,
-                        linvel: shooter.speed * target_dir,
-                    },
-                    Lifespan::new(shooter.lifespan),
-                    EnemyHitbox {
-                        damage: shooter.damage,
-                        once: true,
+                    WaveBundle {
+                        shape_bundle: ShapeBundle {
+                            path: wave.build_path(),
+                            transform: Transform::from_translation(transform.translation()),
+                            ..default()
+                        },
+                        wave,
                     },
+                    Stroke::new(color, 2.0),
                 ));
             }
         }
@@ -134,29 +224,29 @@ impl Plugin {
     fn enemy_damage(
         mut cmd: Commands,
         q_hitbox: Query<&EnemyHitbox>,
-        q_player: Query<&Player>,
+        q_target: Query<(), With<EnemyTarget>>,
         mut ev_collisions: EventReader<CollisionEvent>,
         mut ev_health: EventWriter<HealthChangeEvent>,
     ) {
         for collision in &mut ev_collisions {
-            let player_entity;
+            let target_entity;
             let hitbox;
             let hitbox_entity;
             match collision {
                 CollisionEvent::Started(e1, e2, _) => {
                     if let Ok(h) = q_hitbox.get(*e1) {
-                        if let Ok(_) = q_player.get(*e2) {
+                        if let Ok(_) = q_target.get(*e2) {
                             hitbox = h;
                             hitbox_entity = e1;
-                            player_entity = e2;
+                            target_entity = e2;
                         } else {
                             continue;
                         }
                     } else if let Ok(h) = q_hitbox.get(*e2) {
-                        if let Ok(_) = q_player.get(*e1) {
+                        if let Ok(_) = q_target.get(*e1) {
                             hitbox = h;
                             hitbox_entity = e2;
-                            player_entity = e1;
+                            target_entity = e1;
                         } else {
                             continue;
                         }
@@ -168,7 +258,7 @@ impl Plugin {
             }
 
             ev_health.send(HealthChangeEvent {
-                target: *player_entity,
+                target: *target_entity,
                 amount: -hitbox.damage,
             });
             if hitbox.once {
@@ -180,9 +270,22 @@ impl Plugin {
 
 impl bevy::app::Plugin for Plugin {
     fn build(&self, app: &mut App) {
-        app.add_system(Self::enemy_movement.in_set(Self))
-            .add_system(Self::enemy_shoot.in_set(Self))
-            .add_system(Self::enemy_damage.in_set(Self));
-        app.configure_set(Self.run_if(in_state(GameState::InGame)));
+        app.add_systems(
+            (
+                Self::enemy_movement,
+                Self::enemy_shoot,
+                Self::enemy_pulse,
+                Self::enemy_damage,
+            )
+                .in_set(Self)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        )
+        .edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+            schedule.configure_set(
+                Self.after(crate::steering::Plugin)
+                    .before(crate::force::Plugin)
+                    .run_if(in_state(GameState::InGame)),
+            );
+        });
     }
 }
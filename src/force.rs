@@ -0,0 +1,130 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    wave::{NoEffect, Wave, WaveKind},
+    GameState,
+};
+
+// Acceleration a front at full amplitude applies to anything it passes over.
+const FRONT_FORCE: f32 = 200.0;
+// Rate per second at which knockback fades, exponentially.
+const KNOCKBACK_DECAY: f32 = 4.0;
+
+// How an entity responds to wave forces. Impulses are scaled by
+// `susceptibility / mass` and by the affinity for the kind of wave behind them,
+// which can be negative to pull the entity instead.
+#[derive(Component, Deserialize, Clone, Copy)]
+pub struct WaveSusceptible {
+    pub mass: f32,
+    pub susceptibility: f32,
+    pub positive_affinity: f32,
+    pub negative_affinity: f32,
+}
+
+impl WaveSusceptible {
+    fn response(&self, kind: WaveKind) -> f32 {
+        let affinity = match kind {
+            WaveKind::Positive => self.positive_affinity,
+            WaveKind::Negative => self.negative_affinity,
+        };
+        affinity * self.susceptibility / self.mass
+    }
+}
+
+// Velocity from wave pushes, for entities that set their own velocity every
+// step. They add it on top instead of losing the push.
+#[derive(Component, Default)]
+pub struct Knockback(pub Vec2);
+
+// An instantaneous push on a wave-susceptible entity, before its response is
+// applied.
+pub struct WaveImpulseEvent {
+    pub target: Entity,
+    pub impulse: Vec2,
+    pub kind: WaveKind,
+}
+
+#[derive(SystemSet, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Plugin;
+
+impl Plugin {
+    fn decay_knockback(mut q_knockback: Query<&mut Knockback>, time: Res<FixedTime>) {
+        let decay = f32::exp(-KNOCKBACK_DECAY * time.period.as_secs_f32());
+        for mut knockback in &mut q_knockback {
+            knockback.0 *= decay;
+        }
+    }
+
+    fn front_forces(
+        q_wave: Query<(&Wave, &GlobalTransform)>,
+        mut q_susceptible: Query<
+            (
+                &WaveSusceptible,
+                &GlobalTransform,
+                &mut Velocity,
+                Option<&mut Knockback>,
+            ),
+            Without<NoEffect>,
+        >,
+        time: Res<FixedTime>,
+    ) {
+        for (wave, wave_transform) in &q_wave {
+            let wave_origin = wave_transform.translation().truncate();
+
+            for (susceptible, transform, mut velocity, knockback) in &mut q_susceptible {
+                let offset = transform.translation().truncate() - wave_origin;
+                if !wave.touches(offset) {
+                    continue;
+                }
+                let push = wave.normal_at(offset)
+                    * FRONT_FORCE
+                    * wave.amplitude()
+                    * susceptible.response(wave.kind)
+                    * time.period.as_secs_f32();
+                velocity.linvel += push;
+                if let Some(mut knockback) = knockback {
+                    knockback.0 += push;
+                }
+            }
+        }
+    }
+
+    fn apply_impulses(
+        mut q_susceptible: Query<(&WaveSusceptible, &mut Velocity, Option<&mut Knockback>)>,
+        mut ev_impulse: EventReader<WaveImpulseEvent>,
+    ) {
+        for impulse in &mut ev_impulse {
+            let susceptible = q_susceptible.get_mut(impulse.target);
+            let Ok((susceptible, mut velocity, knockback)) = susceptible else { continue };
+            let push = impulse.impulse * susceptible.response(impulse.kind);
+            velocity.linvel += push;
+            if let Some(mut knockback) = knockback {
+                knockback.0 += push;
+            }
+        }
+    }
+}
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<WaveImpulseEvent>()
+            .add_systems(
+                (
+                    Self::decay_knockback,
+                    Self::front_forces,
+                    Self::apply_impulses,
+                )
+                    .chain()
+                    .in_set(Self)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+                schedule.configure_set(
+                    Self.after(crate::wave::Plugin)
+                        .run_if(in_state(GameState::InGame)),
+                );
+            });
+    }
+}
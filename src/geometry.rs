@@ -0,0 +1,50 @@
+// Geometry shared by interference detection. Nothing here depends on the rest
+// of the game, so `benches/interference.rs` can include the file directly.
+
+use bevy::prelude::*;
+
+// Intersection of two circles, as the midpoint of their common chord and the
+// offset from it to either intersection point.
+pub fn circle_intersection(
+    pos1: Vec2,
+    radius1: f32,
+    pos2: Vec2,
+    radius2: f32,
+) -> Option<(Vec2, Vec2)> {
+    let distance = pos1.distance(pos2);
+    if distance == 0.0 || distance > radius1 + radius2 || distance < f32::abs(radius1 - radius2) {
+        return None;
+    }
+
+    let a = (radius1.powi(2) - radius2.powi(2) + distance.powi(2)) / (2.0 * distance);
+    let h = f32::sqrt(radius1.powi(2) - a.powi(2));
+
+    let center = pos1 + a * (pos2 - pos1) / distance;
+    let offset = Vec2::new(
+        h * (pos2.y - pos1.y) / distance,
+        -h * (pos2.x - pos1.x) / distance,
+    );
+
+    Some((center, offset))
+}
+
+// Sweep-and-prune over the bounding circles of every wave. Fills `pairs` with the
+// waves whose bounds overlap, the only ones whose fronts can intersect.
+pub fn broadphase(bounds: &mut [(Entity, Vec2, f32)], pairs: &mut Vec<(Entity, Entity)>) {
+    pairs.clear();
+    bounds.sort_unstable_by(|(_, pos1, radius1), (_, pos2, radius2)| {
+        (pos1.x - radius1).total_cmp(&(pos2.x - radius2))
+    });
+
+    for (i, (entity1, pos1, radius1)) in bounds.iter().enumerate() {
+        for (entity2, pos2, radius2) in &bounds[i + 1..] {
+            if pos2.x - radius2 > pos1.x + radius1 {
+                break;
+            }
+            if f32::abs(pos1.y - pos2.y) > radius1 + radius2 {
+                continue;
+            }
+            pairs.push((*entity1, *entity2));
+        }
+    }
+}
@@ -16,6 +16,10 @@ impl Health {
     }
 }
 
+// Ignores damage, but can still be healed.
+#[derive(Component)]
+pub struct Invulnerable;
+
 pub struct HealthChangeEvent {
     pub target: Entity,
     pub amount: f32,
@@ -41,11 +45,14 @@ pub struct Plugin;
 impl Plugin {
     fn update_health(
         mut cmd: Commands,
-        mut q_health: Query<(Entity, &mut Health)>,
+        mut q_health: Query<(Entity, &mut Health, Option<&Invulnerable>)>,
         mut event_reader: EventReader<HealthChangeEvent>,
     ) {
         for event in event_reader.iter() {
-            if let Ok((entity, mut health)) = q_health.get_mut(event.target) {
+            if let Ok((entity, mut health, invulnerable)) = q_health.get_mut(event.target) {
+                if invulnerable.is_some() && event.amount < 0.0 {
+                    continue;
+                }
                 health.current += event.amount;
 
                 if health.current > health.max {
@@ -0,0 +1,180 @@
+use bevy::{
+    prelude::*,
+    render::mesh::{Indices, PrimitiveTopology},
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+
+use crate::{
+    wave::{Faction, Wave},
+    GameState, MainCamera,
+};
+
+const CELL_SIZE: f32 = 16.0;
+const MAX_ALPHA: f32 = 0.35;
+const CONSTRUCTIVE_COLOR: Color = Color::YELLOW;
+const DESTRUCTIVE_COLOR: Color = Color::CYAN;
+
+// Grid of vertices covering the viewport, coloured by the interference the
+// player's fronts will produce when they pass over each vertex. Toggled with H.
+#[derive(Component)]
+pub struct Heatmap {
+    columns: usize,
+    rows: usize,
+    origin: Vec2,
+}
+
+impl Heatmap {
+    fn points(&self) -> impl Iterator<Item = Vec2> + '_ {
+        (0..=self.rows).flat_map(move |row| {
+            (0..=self.columns)
+                .map(move |column| self.origin + Vec2::new(column as f32, row as f32) * CELL_SIZE)
+        })
+    }
+}
+
+#[derive(SystemSet, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Plugin;
+
+impl Plugin {
+    fn spawn_heatmap(
+        mut cmd: Commands,
+        q_camera: Query<&Camera, With<MainCamera>>,
+        mut meshes: ResMut<Assets<Mesh>>,
+        mut materials: ResMut<Assets<ColorMaterial>>,
+    ) {
+        let Some(viewport_size) = q_camera.single().logical_viewport_size() else { return };
+
+        let columns = (viewport_size.x / CELL_SIZE).ceil() as usize;
+        let rows = (viewport_size.y / CELL_SIZE).ceil() as usize;
+        let heatmap = Heatmap {
+            columns,
+            rows,
+            origin: -Vec2::new(columns as f32, rows as f32) * CELL_SIZE / 2.0,
+        };
+
+        let positions: Vec<_> = heatmap
+            .points()
+            .map(|point| [point.x, point.y, 0.0])
+            .collect();
+        let mut indices = Vec::with_capacity(columns * rows * 6);
+        for row in 0..rows as u32 {
+            for column in 0..columns as u32 {
+                let corner = row * (columns as u32 + 1) + column;
+                let above = corner + columns as u32 + 1;
+                indices.extend([corner, corner + 1, above, corner + 1, above + 1, above]);
+            }
+        }
+
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, vec![[0.0; 4]; positions.len()]);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.set_indices(Some(Indices::U32(indices)));
+
+        cmd.spawn((
+            MaterialMesh2dBundle {
+                mesh: meshes.add(mesh).into(),
+                material: materials.add(ColorMaterial::from(Color::WHITE)),
+                transform: Transform::from_xyz(0.0, 0.0, -0.09),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            heatmap,
+        ));
+    }
+
+    fn toggle_heatmap(
+        mut q_heatmap: Query<&mut Visibility, With<Heatmap>>,
+        keys: Res<Input<KeyCode>>,
+    ) {
+        if !keys.just_pressed(KeyCode::H) {
+            return;
+        }
+        for mut visibility in &mut q_heatmap {
+            *visibility = match *visibility {
+                Visibility::Hidden => Visibility::Visible,
+                _ => Visibility::Hidden,
+            };
+        }
+    }
+
+    fn update_heatmap(
+        q_heatmap: Query<(&Heatmap, &Mesh2dHandle, &Visibility)>,
+        q_wave: Query<(&Wave, &GlobalTransform)>,
+        mut meshes: ResMut<Assets<Mesh>>,
+    ) {
+        for (heatmap, mesh, visibility) in &q_heatmap {
+            if *visibility == Visibility::Hidden {
+                continue;
+            }
+            let Some(mesh) = meshes.get_mut(&mesh.0) else { continue };
+
+            let colors: Vec<_> = heatmap
+                .points()
+                .map(|point| sample(point, q_wave.iter()))
+                .collect();
+            mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+        }
+    }
+
+    fn cleanup(mut cmd: Commands, q_heatmap: Query<Entity, With<Heatmap>>) {
+        for entity in &q_heatmap {
+            cmd.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+// Colour of the interference at `point` between every player front that will
+// pass through it. Enemy fronts only ever cancel, so they are left out.
+fn sample<'a>(
+    point: Vec2,
+    waves: impl Iterator<Item = (&'a Wave, &'a GlobalTransform)>,
+) -> [f32; 4] {
+    let mut sum = Vec2::ZERO;
+    let mut energy = 0.0;
+    let mut count = 0;
+
+    for (wave, transform) in waves {
+        if wave.faction != Faction::Player {
+            continue;
+        }
+        let offset = point - transform.translation().truncate();
+        let distance = wave.travel_distance(offset);
+        if distance < 0.0 || distance > wave.profile.max_radius || !wave.covers(offset) {
+            continue;
+        }
+
+        let amplitude =
+            (1.0 - (distance / wave.profile.max_radius).powi(2)) * wave.doppler_factor(offset);
+        sum += Vec2::from_angle(wave.phase_at(offset)) * amplitude;
+        energy += amplitude.powi(2);
+        count += 1;
+    }
+
+    if count < 2 {
+        return [0.0; 4];
+    }
+
+    // Twice the sum of the products of every pair of amplitudes and their coherence.
+    let interference = sum.length_squared() - energy;
+    let color = if interference >= 0.0 {
+        CONSTRUCTIVE_COLOR
+    } else {
+        DESTRUCTIVE_COLOR
+    };
+    let [r, g, b, _] = color.as_linear_rgba_f32();
+    [r, g, b, MAX_ALPHA * interference.abs().min(1.0)]
+}
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(Self::spawn_heatmap.in_schedule(OnEnter(GameState::InGame)))
+            .add_system(Self::toggle_heatmap.in_set(Self))
+            .add_system(
+                Self::update_heatmap
+                    .after(Self::toggle_heatmap)
+                    .in_set(Self),
+            )
+            .add_system(Self::cleanup.in_schedule(OnExit(GameState::InGame)));
+        app.configure_set(Self.run_if(in_state(GameState::InGame)));
+    }
+}
@@ -2,12 +2,24 @@ use bevy::prelude::*;
 use bevy_prototype_lyon::prelude::*;
 use bevy_rapier2d::prelude::*;
 
+mod archetype;
+mod arena;
+mod boss;
 mod director;
+mod emitter;
 mod enemy;
+mod force;
 mod health;
+mod heatmap;
 mod game_over;
+mod geometry;
 mod main_menu;
+mod pattern;
 mod player;
+mod profile;
+mod projectile;
+mod resonance;
+mod steering;
 mod utils;
 mod wave;
 
@@ -19,13 +31,64 @@ pub enum GameState {
     GameOver,
 }
 
+const TIMESTEP: f32 = 1.0 / 60.0;
+
+// Base sets of `CoreSchedule::FixedUpdate`. Gameplay runs after each physics
+// step, between restoring the simulated positions that rendering interpolated
+// away from and recording the new ones.
+#[derive(SystemSet, Clone, Debug, Hash, PartialEq, Eq)]
+#[system_set(base)]
+pub enum FixedSet {
+    Restore,
+    Gameplay,
+    Record,
+}
+
 fn main() {
     let mut app = App::new();
 
+    app.edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+        schedule
+            .set_default_base_set(FixedSet::Gameplay)
+            .configure_sets(
+                (
+                    FixedSet::Restore,
+                    PhysicsSet::SyncBackend,
+                    PhysicsSet::SyncBackendFlush,
+                    PhysicsSet::StepSimulation,
+                    PhysicsSet::Writeback,
+                    FixedSet::Gameplay,
+                    FixedSet::Record,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::SyncBackend)
+                    .in_base_set(PhysicsSet::SyncBackend),
+            )
+            .add_systems(
+                RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::SyncBackendFlush)
+                    .in_base_set(PhysicsSet::SyncBackendFlush),
+            )
+            .add_systems(
+                RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::StepSimulation)
+                    .in_base_set(PhysicsSet::StepSimulation),
+            )
+            .add_systems(
+                RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::Writeback)
+                    .in_base_set(PhysicsSet::Writeback),
+            );
+    });
+
     app.insert_resource(RapierConfiguration {
         gravity: Vec2::ZERO,
+        timestep_mode: TimestepMode::Fixed {
+            dt: TIMESTEP,
+            substeps: 1,
+        },
         ..default()
     })
+    .insert_resource(FixedTime::new_from_secs(TIMESTEP))
     .insert_resource(ClearColor(Color::hex("0a0a0a").unwrap()))
     .add_state::<GameState>()
     .add_plugins(DefaultPlugins.set(WindowPlugin{
@@ -36,15 +99,28 @@ fn main() {
 
             }),
             ..default()
+    }).set(AssetPlugin {
+        watch_for_changes: cfg!(feature = "hot_reload"),
+        ..default()
     }))
-    .add_plugin(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugin(RapierPhysicsPlugin::<NoUserData>::default().with_default_system_setup(false))
     .add_plugin(ShapePlugin)
+    .add_plugin(archetype::Plugin)
+    .add_plugin(arena::Plugin)
+    .add_plugin(boss::Plugin)
     .add_plugin(director::Plugin)
+    .add_plugin(emitter::Plugin)
     .add_plugin(enemy::Plugin)
+    .add_plugin(force::Plugin)
     .add_plugin(main_menu::Plugin)
     .add_plugin(game_over::Plugin)
     .add_plugin(health::Plugin)
+    .add_plugin(heatmap::Plugin)
     .add_plugin(player::Plugin)
+    .add_plugin(profile::Plugin)
+    .add_plugin(projectile::Plugin)
+    .add_plugin(resonance::Plugin)
+    .add_plugin(steering::Plugin)
     .add_plugin(utils::Plugin)
     .add_plugin(wave::Plugin);
 
@@ -0,0 +1,159 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+// What a pattern's angles are measured from when a shot leaves the enemy.
+#[derive(Deserialize, Clone, Copy, Default)]
+pub enum Aim {
+    // Where the player will be when the shot arrives, if they keep moving.
+    #[default]
+    Predicted,
+    // Where the player is now.
+    Direct,
+    // A fixed world angle, in radians from the x axis.
+    Fixed(f32),
+}
+
+// A volley of shots, composed from smaller patterns. Angles are in radians and
+// delays in seconds, both relative to the pattern they're nested in.
+#[derive(Deserialize, Clone)]
+pub enum Pattern {
+    // One shot straight along the aim.
+    Single,
+    // `count` shots evenly across `width`, centred on the aim.
+    Spread {
+        count: u32,
+        width: f32,
+    },
+    // `count` shots evenly around a full circle, starting at the aim.
+    Ring {
+        count: u32,
+    },
+    // `count` shots `interval` apart, each turned `step` further than the last.
+    Spiral {
+        count: u32,
+        interval: f32,
+        step: f32,
+    },
+    // Fires `pattern` `count` times, `interval` apart.
+    Burst {
+        pattern: Box<Pattern>,
+        count: u32,
+        interval: f32,
+    },
+    // Turns `pattern` `step` further every volley.
+    Rotating {
+        pattern: Box<Pattern>,
+        step: f32,
+    },
+    // Measures `pattern` from `aim` instead of the predicted player position.
+    Aimed {
+        pattern: Box<Pattern>,
+        aim: Aim,
+    },
+    // Fires the next pattern in the list every volley.
+    Alternating(Vec<Pattern>),
+    // Fires every pattern in the list at once.
+    Layered(Vec<Pattern>),
+}
+
+#[derive(Clone, Copy)]
+pub struct Shot {
+    pub delay: f32,
+    pub aim: Aim,
+    pub angle: f32,
+}
+
+impl Shot {
+    // Direction the shot leaves in, given where it was aimed.
+    pub fn direction(&self, aim_direction: Vec2) -> Vec2 {
+        Vec2::from_angle(self.angle).rotate(aim_direction)
+    }
+}
+
+impl Pattern {
+    // The shots making up the `volley`th time the pattern is fired.
+    pub fn volley(&self, volley: u32) -> Vec<Shot> {
+        let mut shots = Vec::new();
+        self.collect(
+            volley,
+            Shot {
+                delay: 0.0,
+                aim: Aim::default(),
+                angle: 0.0,
+            },
+            &mut shots,
+        );
+        shots
+    }
+
+    fn collect(&self, volley: u32, base: Shot, shots: &mut Vec<Shot>) {
+        match self {
+            Pattern::Single => shots.push(base),
+            Pattern::Spread { count, width } => {
+                for i in 0..*count {
+                    let offset = if *count > 1 {
+                        width * (i as f32 / (count - 1) as f32 - 0.5)
+                    } else {
+                        0.0
+                    };
+                    shots.push(Shot {
+                        angle: base.angle + offset,
+                        ..base
+                    });
+                }
+            }
+            Pattern::Ring { count } => {
+                for i in 0..*count {
+                    shots.push(Shot {
+                        angle: base.angle + TAU * i as f32 / *count as f32,
+                        ..base
+                    });
+                }
+            }
+            Pattern::Spiral {
+                count,
+                interval,
+                step,
+            } => {
+                for i in 0..*count {
+                    shots.push(Shot {
+                        delay: base.delay + interval * i as f32,
+                        angle: base.angle + step * i as f32,
+                        ..base
+                    });
+                }
+            }
+            Pattern::Burst {
+                pattern,
+                count,
+                interval,
+            } => {
+                for i in 0..*count {
+                    let delay = base.delay + interval * i as f32;
+                    pattern.collect(volley, Shot { delay, ..base }, shots);
+                }
+            }
+            Pattern::Rotating { pattern, step } => {
+                let angle = base.angle + step * volley as f32;
+                pattern.collect(volley, Shot { angle, ..base }, shots);
+            }
+            Pattern::Aimed { pattern, aim } => {
+                pattern.collect(volley, Shot { aim: *aim, ..base }, shots);
+            }
+            Pattern::Alternating(patterns) => {
+                if patterns.is_empty() {
+                    return;
+                }
+                let pattern = &patterns[volley as usize % patterns.len()];
+                pattern.collect(volley / patterns.len() as u32, base, shots);
+            }
+            Pattern::Layered(patterns) => {
+                for pattern in patterns {
+                    pattern.collect(volley, base, shots);
+                }
+            }
+        }
+    }
+}
@@ -3,17 +3,27 @@ use bevy_prototype_lyon::prelude::*;
 use bevy_rapier2d::prelude::*;
 
 use crate::{
+    emitter::EmitterLaunch,
+    enemy::EnemyTarget,
     health::{Health, HealthBar},
-    utils::{Lifespan, MousePosition, PlaySound},
-    wave::{DelayedWave, Wave, WaveBundle, WaveKind},
+    profile::WaveProfiles,
+    utils::{MousePosition, PlaySound},
+    wave::{
+        DelayedWave, DopplerSource, Faction, Wave, WaveBundle, WaveKind, WaveRider, WaveShape,
+    },
     GameState,
 };
 
 const PLAYER_SPEED: f32 = 200.0;
+const ARC_WIDTH: f32 = std::f32::consts::FRAC_PI_2;
+const LINE_HALF_LENGTH: f32 = 80.0;
 
 #[derive(Resource)]
 pub struct AvgPlayerVel(pub Vec2);
 
+#[derive(Resource, Default)]
+struct MovementInput(Vec2);
+
 #[derive(Component)]
 pub struct Player {
     wave_cooldown: Timer,
@@ -49,6 +59,11 @@ impl Plugin {
             RigidBody::Dynamic,
             Velocity::default(),
             Health::new(100.0),
+            EnemyTarget,
+            WaveRider {
+                along: 0.5,
+                against: 0.4,
+            },
         ))
         .with_children(|parent| {
             parent.spawn((
@@ -115,14 +130,10 @@ impl Plugin {
         });
     }
 
-    fn player_movement(
-        mut q_player: Query<&mut Velocity, With<Player>>,
-        keys: Res<Input<KeyCode>>,
-        mut input_direction: Local<Vec2>,
-        mut avg_vel: ResMut<AvgPlayerVel>,
-        time: Res<Time>,
-    ) {
-        let Ok(mut player_vel) = q_player.get_single_mut() else { return };
+    // Key presses only last a frame, so the direction is tracked every frame
+    // rather than on the fixed steps that apply it.
+    fn read_movement_input(keys: Res<Input<KeyCode>>, mut input: ResMut<MovementInput>) {
+        let input_direction = &mut input.0;
 
         if !keys.pressed(KeyCode::A) && !keys.pressed(KeyCode::D) {
             input_direction.x = 0.0;
@@ -173,10 +184,26 @@ impl Plugin {
                 input_direction.y = 0.0;
             }
         }
+    }
+
+    fn player_movement(
+        mut q_player: Query<(&mut Velocity, &GlobalTransform, &WaveRider), With<Player>>,
+        q_wave: Query<(&Wave, &GlobalTransform)>,
+        input: Res<MovementInput>,
+        mut avg_vel: ResMut<AvgPlayerVel>,
+        time: Res<FixedTime>,
+    ) {
+        let Ok((mut player_vel, player_transform, rider)) = q_player.get_single_mut() else { return };
+        let input_direction = input.0;
 
-        player_vel.linvel = input_direction.normalize_or_zero() * PLAYER_SPEED;
+        let modifier = rider.speed_modifier(
+            player_transform.translation().truncate(),
+            input_direction,
+            q_wave.iter(),
+        );
+        player_vel.linvel = input_direction.normalize_or_zero() * PLAYER_SPEED * modifier;
 
-        let alpha = 0.5 * time.delta_seconds();
+        let alpha = 0.5 * time.period.as_secs_f32();
 
         let prev = avg_vel.0;
         avg_vel.0 += alpha * (player_vel.linvel - prev);
@@ -184,51 +211,68 @@ impl Plugin {
 
     fn spawn_wave(
         mut cmd: Commands,
-        mut q_player: Query<(&GlobalTransform, &mut Player)>,
+        mut q_player: Query<(Entity, &GlobalTransform, &mut Player)>,
         mouse_buttons: Res<Input<MouseButton>>,
+        keys: Res<Input<KeyCode>>,
+        mouse_position: Res<MousePosition>,
         mut ev_sound: EventWriter<PlaySound>,
+        profiles: WaveProfiles,
     ) {
-        let Ok((player_transform, mut player)) = q_player.get_single_mut () else { return };
+        let Ok((player_entity, player_transform, mut player)) = q_player.get_single_mut () else { return };
         if !player.wave_cooldown.finished() {
             return;
         }
 
         let wave_transform = player_transform.compute_transform();
 
-        if mouse_buttons.pressed(MouseButton::Left) {
-            player.wave_cooldown.reset();
-            cmd.spawn((
-                WaveBundle {
-                    wave: Wave {
-                        kind: WaveKind::Positive,
-                        radius: 0.0,
-                        speed: 100.0,
-                        max_radius: 400.0,
-                    },
-                    shape_bundle: ShapeBundle {
-                        path: GeometryBuilder::build_as(&shapes::Circle {
-                            radius: 0.0,
-                            center: Vec2::ZERO,
-                        }),
-                        transform: wave_transform,
-                        ..default()
-                    },
-                },
-                Stroke::new(Color::RED, 2.0),
-            ));
-            cmd.spawn(DelayedWave::new(
-                Wave {
-                    kind: WaveKind::Negative,
-                    radius: 0.0,
-                    max_radius: 400.0,
-                    speed: 100.0,
-                },
-                wave_transform,
-                0.5,
-            ));
+        let aim = (mouse_position.0 - player_transform.translation()).truncate();
+        let shape = if mouse_buttons.pressed(MouseButton::Left) {
+            WaveShape::Ring
+        } else if keys.pressed(KeyCode::Space) {
+            WaveShape::Arc {
+                direction: aim.y.atan2(aim.x),
+                width: ARC_WIDTH,
+            }
+        } else if keys.pressed(KeyCode::E) {
+            WaveShape::Line {
+                direction: aim.y.atan2(aim.x),
+                half_length: LINE_HALF_LENGTH,
+            }
+        } else {
+            return;
+        };
+
+        player.wave_cooldown.reset();
 
-            ev_sound.send(PlaySound("ding.ogg".to_owned()));
+        let wave = profiles.wave("player", WaveKind::Positive, Faction::Player, shape);
+
+        // Holding shift keeps emitting from the player as they move instead.
+        if keys.pressed(KeyCode::LShift) {
+            cmd.spawn(DopplerSource::new(player_entity, wave, 0.5, 2));
+            return;
         }
+
+        cmd.spawn(DelayedWave::new(
+            Wave {
+                kind: WaveKind::Negative,
+                ..wave.clone()
+            },
+            wave_transform,
+            0.5,
+        ));
+        cmd.spawn((
+            WaveBundle {
+                shape_bundle: ShapeBundle {
+                    path: wave.build_path(),
+                    transform: wave_transform,
+                    ..default()
+                },
+                wave,
+            },
+            Stroke::new(Color::RED, 2.0),
+        ));
+
+        ev_sound.send(PlaySound("ding.ogg".to_owned()));
     }
 
     fn spawn_emitter(
@@ -244,27 +288,6 @@ impl Plugin {
         let player_pos = player_transform.translation();
         if mouse_buttons.pressed(MouseButton::Right) {
             player.emitter_cooldown.reset();
-            cmd.spawn(DelayedWave::new(
-                Wave {
-                    kind: WaveKind::Positive,
-                    radius: 0.0,
-                    max_radius: 400.0,
-                    speed: 100.0,
-                },
-                Transform::from_translation(mouse_position.0),
-                mouse_position.0.distance(player_pos) / 1200.0,
-            ));
-
-            cmd.spawn(DelayedWave::new(
-                Wave {
-                    kind: WaveKind::Negative,
-                    radius: 0.0,
-                    max_radius: 400.0,
-                    speed: 100.0,
-                },
-                Transform::from_translation(mouse_position.0),
-                0.5 + mouse_position.0.distance(player_pos) / 1200.0,
-            ));
 
             cmd.spawn((
                 SpriteBundle {
@@ -280,16 +303,19 @@ impl Plugin {
                     linvel: (mouse_position.0 - player_pos).truncate().normalize() * 1200.0,
                     angvel: 100.0,
                 },
-                Lifespan::new(mouse_position.0.distance(player_pos) / 1200.0),
+                EmitterLaunch::new(
+                    mouse_position.0,
+                    mouse_position.0.distance(player_pos) / 1200.0,
+                ),
             ));
         }
     }
 
-    fn update_cooldowns(mut q_player: Query<&mut Player>, time: Res<Time>) {
+    fn update_cooldowns(mut q_player: Query<&mut Player>, time: Res<FixedTime>) {
         let Ok(mut player) = q_player.get_single_mut() else { return };
 
-        player.emitter_cooldown.tick(time.delta());
-        player.wave_cooldown.tick(time.delta());
+        player.emitter_cooldown.tick(time.period);
+        player.wave_cooldown.tick(time.period);
     }
 
     fn update_wave_indicator(
@@ -345,13 +371,24 @@ impl bevy::app::Plugin for Plugin {
     fn build(&self, app: &mut App) {
         app.add_system(Self::spawn_player.in_schedule(OnEnter(GameState::InGame)))
             .insert_resource(AvgPlayerVel(Vec2::ZERO))
-            .add_system(Self::player_movement.in_set(Self))
-            .add_system(Self::spawn_wave.in_set(Self))
-            .add_system(Self::spawn_emitter.in_set(Self))
-            .add_system(Self::update_cooldowns.in_set(Self))
+            .init_resource::<MovementInput>()
+            .add_system(Self::read_movement_input.in_set(Self))
+            .add_systems(
+                (
+                    Self::player_movement,
+                    Self::spawn_wave,
+                    Self::spawn_emitter,
+                    Self::update_cooldowns,
+                )
+                    .in_set(Self)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
             .add_system(Self::update_wave_indicator.in_set(Self))
             .add_system(Self::update_emitter_indicator.in_set(Self))
-            .add_system(Self::end_game.in_set(Self));
+            .add_system(Self::end_game.in_set(Self))
+            .edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+                schedule.configure_set(Self.run_if(in_state(GameState::InGame)));
+            });
 
         app.configure_set(Self.run_if(in_state(GameState::InGame)));
     }
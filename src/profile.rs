@@ -0,0 +1,150 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    ecs::system::SystemParam,
+    prelude::*,
+    reflect::TypeUuid,
+    utils::{BoxedFuture, HashMap},
+};
+use serde::Deserialize;
+
+use crate::{
+    wave::{Faction, Wave, WaveKind, WaveShape},
+    GameState,
+};
+
+// Loaded from `assets/waves/<name>.wave.ron`.
+const PROFILES: [&str; 4] = ["player", "emitter", "resonance", "pulser"];
+
+#[derive(Deserialize, TypeUuid, Clone, Debug)]
+#[uuid = "bcbbd793-f5a7-4eb2-9a13-bcdcd2c0c966"]
+#[serde(default)]
+pub struct WaveProfile {
+    pub speed: f32,
+    pub max_radius: f32,
+    pub wavelength: f32,
+    // The front is drawn `line_width + line_growth * (radius / max_radius)²` wide.
+    pub line_width: f32,
+    pub line_growth: f32,
+    // Opacity falls off as `1 - (radius / max_radius)^fade`.
+    pub fade: f32,
+    // Health per second taken from anything the front passes over.
+    pub damage: f32,
+    pub echo: Option<Echo>,
+}
+
+// Where the rings left behind by an expiring wave come from.
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub enum EchoPattern {
+    // Same-kind rings from wherever the front reached straight up, down, left
+    // and right of its origin.
+    Cardinal,
+    // A single ring of the opposite kind from the original origin.
+    Inverted,
+}
+
+// Makes a wave spawn weaker rings when it reaches its maximum radius, whose
+// radius and damage are scaled by `falloff`. Those echo again until `depth`
+// generations have been spawned.
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct Echo {
+    pub pattern: EchoPattern,
+    pub depth: u32,
+    pub falloff: f32,
+}
+
+impl Default for WaveProfile {
+    fn default() -> Self {
+        WaveProfile {
+            speed: 100.0,
+            max_radius: 400.0,
+            wavelength: 50.0,
+            line_width: 2.0,
+            line_growth: 30.0,
+            fade: 2.0,
+            damage: 10.0,
+            echo: None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct WaveProfileLoader;
+
+impl AssetLoader for WaveProfileLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let profile: WaveProfile = ron::de::from_bytes(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(profile));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["wave.ron"]
+    }
+}
+
+#[derive(Resource, Default)]
+struct ProfileHandles(HashMap<String, Handle<WaveProfile>>);
+
+#[derive(SystemParam)]
+pub struct WaveProfiles<'w> {
+    handles: Res<'w, ProfileHandles>,
+    assets: Res<'w, Assets<WaveProfile>>,
+}
+
+impl WaveProfiles<'_> {
+    // Uses the default profile until the named file has finished loading.
+    pub fn wave(&self, name: &str, kind: WaveKind, faction: Faction, shape: WaveShape) -> Wave {
+        let handle = self.handles.0.get(name).cloned().unwrap_or_default();
+        let profile = self.assets.get(&handle).cloned().unwrap_or_default();
+        Wave::new(kind, faction, shape, handle, profile)
+    }
+}
+
+#[derive(SystemSet, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Plugin;
+
+impl Plugin {
+    fn load_profiles(mut handles: ResMut<ProfileHandles>, assets: Res<AssetServer>) {
+        for name in PROFILES {
+            handles.0.insert(
+                name.to_owned(),
+                assets.load(format!("waves/{name}.wave.ron")),
+            );
+        }
+    }
+
+    // Carries edits to a profile file over to waves already in flight.
+    fn reload_profiles(
+        mut q_wave: Query<&mut Wave>,
+        mut ev_asset: EventReader<AssetEvent<WaveProfile>>,
+        profiles: Res<Assets<WaveProfile>>,
+    ) {
+        for event in &mut ev_asset {
+            let AssetEvent::Modified { handle } = event else { continue };
+            let Some(profile) = profiles.get(handle) else { continue };
+
+            for mut wave in &mut q_wave {
+                if wave.profile_handle == *handle {
+                    wave.profile = profile.clone();
+                }
+            }
+        }
+    }
+}
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<WaveProfile>()
+            .init_asset_loader::<WaveProfileLoader>()
+            .init_resource::<ProfileHandles>()
+            .add_startup_system(Self::load_profiles)
+            .add_system(Self::reload_profiles.in_set(Self));
+        app.configure_set(Self.run_if(in_state(GameState::InGame)));
+    }
+}
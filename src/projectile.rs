@@ -0,0 +1,204 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    enemy::EnemyHitbox, force::WaveSusceptible, pattern::Pattern, player::Player, GameState,
+};
+
+// How a projectile steers and changes speed once it's been flying for `delay`
+// seconds. Until then it keeps the velocity it was fired with.
+#[derive(Deserialize, Clone, Default)]
+pub struct ProjectileBehaviour {
+    #[serde(default)]
+    pub delay: f32,
+    #[serde(default)]
+    pub homing: Option<Homing>,
+    #[serde(default)]
+    pub acceleration: Option<Acceleration>,
+    #[serde(default)]
+    pub split: Option<Split>,
+}
+
+// Turns towards the player at up to `turn_rate` radians per second.
+#[derive(Deserialize, Clone, Copy)]
+pub struct Homing {
+    pub turn_rate: f32,
+}
+
+// Speeds up, or slows down if `rate` is negative, until it reaches `limit`.
+// Pushes from waves are kept rather than undone.
+#[derive(Deserialize, Clone, Copy)]
+pub struct Acceleration {
+    pub rate: f32,
+    pub limit: f32,
+}
+
+// Bursts into `pattern` when its lifespan runs out, measured from the
+// direction it was moving in. The pieces fly straight, and any delays in the
+// pattern are ignored.
+#[derive(Deserialize, Clone)]
+pub struct Split {
+    pub pattern: Pattern,
+    pub speed: f32,
+    pub lifespan: f32,
+}
+
+// Everything about a projectile except where it's fired from and in which
+// direction.
+#[derive(Clone)]
+pub struct Projectile {
+    pub speed: f32,
+    pub lifespan: f32,
+    pub damage: f32,
+    pub size: f32,
+    pub texture: Handle<Image>,
+    pub behaviour: ProjectileBehaviour,
+}
+
+#[derive(Component)]
+struct Fired {
+    projectile: Projectile,
+    age: f32,
+}
+
+impl Projectile {
+    pub fn spawn(&self, cmd: &mut Commands, position: Vec3, direction: Vec2) {
+        cmd.spawn((
+            SpriteBundle {
+                texture: self.texture.clone(),
+                transform: Transform::from_translation(position).with_rotation(facing(direction)),
+                ..default()
+            },
+            Collider::ball(self.size),
+            Sensor,
+            RigidBody::KinematicVelocityBased,
+            ActiveEvents::COLLISION_EVENTS,
+            ActiveCollisionTypes::KINEMATIC_STATIC | ActiveCollisionTypes::KINEMATIC_KINEMATIC,
+            Velocity {
+                angvel: 0.0,
+                linvel: self.speed * direction,
+            },
+            WaveSusceptible {
+                mass: 1.0,
+                susceptibility: 1.0,
+                positive_affinity: 0.0,
+                negative_affinity: 1.0,
+            },
+            EnemyHitbox {
+                damage: self.damage,
+                once: true,
+            },
+            Fired {
+                projectile: self.clone(),
+                age: 0.0,
+            },
+        ));
+    }
+}
+
+fn facing(direction: Vec2) -> Quat {
+    Quat::from_rotation_z(-direction.angle_between(Vec2::NEG_Y))
+}
+
+#[derive(SystemSet, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Plugin;
+
+impl Plugin {
+    fn steer(
+        q_player: Query<&GlobalTransform, With<Player>>,
+        mut q_fired: Query<(&mut Fired, &mut Velocity, &mut Transform)>,
+        time: Res<FixedTime>,
+    ) {
+        let dt = time.period.as_secs_f32();
+        let player_pos = q_player
+            .get_single()
+            .ok()
+            .map(|transform| transform.translation().truncate());
+
+        for (mut fired, mut velocity, mut transform) in &mut q_fired {
+            fired.age += dt;
+            let behaviour = &fired.projectile.behaviour;
+            if fired.age < behaviour.delay {
+                continue;
+            }
+
+            let to_player = player_pos
+                .map_or(Vec2::ZERO, |player_pos| {
+                    player_pos - transform.translation.truncate()
+                })
+                .normalize_or_zero();
+
+            if let Some(homing) = behaviour.homing {
+                let turn = velocity.linvel.angle_between(to_player);
+                if turn.is_finite() {
+                    let max_turn = homing.turn_rate * dt;
+                    velocity.linvel =
+                        Vec2::from_angle(turn.clamp(-max_turn, max_turn)).rotate(velocity.linvel);
+                }
+            }
+
+            if let Some(acceleration) = behaviour.acceleration {
+                // Projectiles fired at rest pick up speed towards the player.
+                let direction = velocity.linvel.try_normalize().unwrap_or(to_player);
+                let speed = velocity.linvel.length();
+                let accelerated = speed + acceleration.rate * dt;
+                let speed = if acceleration.rate >= 0.0 {
+                    accelerated.min(acceleration.limit.max(speed))
+                } else {
+                    accelerated.max(acceleration.limit.min(speed))
+                };
+                velocity.linvel = direction * speed.max(0.0);
+            }
+
+            if let Some(direction) = velocity.linvel.try_normalize() {
+                transform.rotation = facing(direction);
+            }
+        }
+    }
+
+    fn expire(mut cmd: Commands, q_fired: Query<(Entity, &Fired, &Velocity, &GlobalTransform)>) {
+        for (entity, fired, velocity, transform) in &q_fired {
+            if fired.age < fired.projectile.lifespan {
+                continue;
+            }
+            cmd.entity(entity).despawn_recursive();
+
+            let Some(split) = &fired.projectile.behaviour.split else { continue };
+            let piece = Projectile {
+                speed: split.speed,
+                lifespan: split.lifespan,
+                behaviour: ProjectileBehaviour::default(),
+                ..fired.projectile.clone()
+            };
+            let direction = velocity.linvel.try_normalize().unwrap_or(Vec2::NEG_Y);
+            for shot in split.pattern.volley(0) {
+                piece.spawn(&mut cmd, transform.translation(), shot.direction(direction));
+            }
+        }
+    }
+
+    fn cleanup(mut cmd: Commands, q_fired: Query<Entity, With<Fired>>) {
+        for entity in &q_fired {
+            cmd.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (Self::steer, Self::expire)
+                .chain()
+                .in_set(Self)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        )
+        .add_system(Self::cleanup.in_schedule(OnExit(GameState::InGame)))
+        .edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+            schedule.configure_set(
+                Self.after(crate::force::Plugin)
+                    .run_if(in_state(GameState::InGame)),
+            );
+        });
+    }
+}
@@ -0,0 +1,193 @@
+use bevy::prelude::*;
+use bevy_prototype_lyon::prelude::*;
+
+use crate::{
+    enemy::{Enemy, EnemyHitbox},
+    health::HealthChangeEvent,
+    profile::WaveProfiles,
+    utils::PlaySound,
+    wave::{Faction, InterferenceKind, WaveBundle, WaveInterferenceEvent, WaveKind, WaveShape},
+    GameState,
+};
+
+const SITE_RADIUS: f32 = 60.0;
+const CHARGE_RATE: f32 = 1.5;
+const CHARGE_THRESHOLD: f32 = 1.0;
+const CHARGE_DECAY: f32 = 0.5;
+const WINDOW: f32 = 1.0;
+
+const BURST_RADIUS: f32 = 120.0;
+const BURST_DAMAGE: f32 = 30.0;
+
+// An area that constructive interference has kept hitting. Charge only drains
+// once the site has gone `WINDOW` seconds without being hit.
+#[derive(Component)]
+pub struct ResonanceSite {
+    kind: WaveKind,
+    charge: f32,
+    idle_timer: Timer,
+}
+
+#[derive(SystemSet, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Plugin;
+
+impl Plugin {
+    fn accumulate_charge(
+        mut cmd: Commands,
+        mut q_site: Query<(&mut ResonanceSite, &GlobalTransform)>,
+        mut ev_interference: EventReader<WaveInterferenceEvent>,
+        mut new_sites: Local<Vec<(WaveKind, Vec2, f32)>>,
+        time: Res<FixedTime>,
+    ) {
+        for interference in &mut ev_interference {
+            let kind = match interference.kind() {
+                InterferenceKind::Positive => WaveKind::Positive,
+                InterferenceKind::Negative => WaveKind::Negative,
+                InterferenceKind::Destructive => continue,
+            };
+            let charge = interference.strength() * CHARGE_RATE * time.period.as_secs_f32();
+
+            let site = q_site.iter_mut().find(|(site, transform)| {
+                site.kind == kind
+                    && transform
+                        .translation()
+                        .truncate()
+                        .distance(interference.position())
+                        <= SITE_RADIUS
+            });
+
+            if let Some((mut site, _)) = site {
+                site.charge += charge;
+                site.idle_timer.reset();
+            } else if let Some(new_site) = new_sites.iter_mut().find(|(site_kind, position, _)| {
+                *site_kind == kind && position.distance(interference.position()) <= SITE_RADIUS
+            }) {
+                new_site.2 += charge;
+            } else {
+                new_sites.push((kind, interference.position(), charge));
+            }
+        }
+
+        for (kind, position, charge) in new_sites.drain(..) {
+            cmd.spawn((
+                ShapeBundle {
+                    transform: Transform::from_translation(position.extend(-0.05)),
+                    ..default()
+                },
+                Stroke::new(kind.color(), 2.0),
+                ResonanceSite {
+                    kind,
+                    charge,
+                    idle_timer: Timer::from_seconds(WINDOW, TimerMode::Once),
+                },
+            ));
+        }
+    }
+
+    fn update_sites(
+        mut cmd: Commands,
+        mut q_site: Query<(
+            Entity,
+            &mut ResonanceSite,
+            &GlobalTransform,
+            &mut Path,
+            &mut Stroke,
+        )>,
+        q_enemy: Query<(Entity, &GlobalTransform), With<Enemy>>,
+        q_projectile: Query<(Entity, &GlobalTransform), (With<EnemyHitbox>, Without<Enemy>)>,
+        mut ev_health: EventWriter<HealthChangeEvent>,
+        mut ev_sound: EventWriter<PlaySound>,
+        profiles: WaveProfiles,
+        time: Res<FixedTime>,
+    ) {
+        for (entity, mut site, transform, mut path, mut stroke) in &mut q_site {
+            site.idle_timer.tick(time.period);
+            if site.idle_timer.finished() {
+                site.charge -= CHARGE_DECAY * time.period.as_secs_f32();
+                if site.charge <= 0.0 {
+                    cmd.entity(entity).despawn_recursive();
+                    continue;
+                }
+            }
+
+            let fraction = (site.charge / CHARGE_THRESHOLD).min(1.0);
+            *path = GeometryBuilder::build_as(&shapes::Circle {
+                radius: SITE_RADIUS * fraction,
+                center: Vec2::ZERO,
+            });
+            stroke.color.set_a(0.2 + 0.6 * fraction);
+
+            if site.charge < CHARGE_THRESHOLD {
+                continue;
+            }
+
+            let position = transform.translation().truncate();
+            match site.kind {
+                WaveKind::Positive => {
+                    for (enemy, enemy_transform) in &q_enemy {
+                        if enemy_transform.translation().truncate().distance(position)
+                            <= BURST_RADIUS
+                        {
+                            ev_health.send(HealthChangeEvent {
+                                target: enemy,
+                                amount: -BURST_DAMAGE,
+                            });
+                        }
+                    }
+                }
+                WaveKind::Negative => {
+                    for (projectile, projectile_transform) in &q_projectile {
+                        if projectile_transform
+                            .translation()
+                            .truncate()
+                            .distance(position)
+                            <= BURST_RADIUS
+                        {
+                            cmd.entity(projectile).despawn_recursive();
+                        }
+                    }
+                }
+            }
+
+            let wave = profiles.wave("resonance", site.kind, Faction::Player, WaveShape::Ring);
+            let color = wave.color();
+            cmd.spawn((
+                WaveBundle {
+                    shape_bundle: ShapeBundle {
+                        path: wave.build_path(),
+                        transform: transform.compute_transform(),
+                        ..default()
+                    },
+                    wave,
+                },
+                Stroke::new(color, 2.0),
+            ));
+            cmd.entity(entity).despawn_recursive();
+            ev_sound.send(PlaySound("ding.ogg".to_owned()));
+        }
+    }
+
+    fn cleanup(mut cmd: Commands, q_site: Query<Entity, With<ResonanceSite>>) {
+        for entity in &q_site {
+            cmd.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (Self::accumulate_charge, Self::update_sites)
+                .chain()
+                .in_set(Self)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        )
+        .add_system(Self::cleanup.in_schedule(OnExit(GameState::InGame)))
+        .edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+            schedule.configure_set(
+                Self.after(crate::wave::Plugin)
+                    .run_if(in_state(GameState::InGame)),
+            );
+        });
+    }
+}
@@ -0,0 +1,175 @@
+use bevy::{prelude::*, utils::HashMap};
+use serde::Deserialize;
+
+use crate::{enemy::Enemy, GameState};
+
+// Side of the grid cells enemies are bucketed into for separation. Radii larger
+// than this just search more cells.
+const CELL_SIZE: f32 = 64.0;
+
+// Something an enemy wants to do relative to its target, as a direction no
+// longer than one.
+#[derive(Deserialize, Clone, Copy)]
+pub enum Behaviour {
+    // Straight at the target.
+    Seek,
+    // Straight at the target, slowing down within `radius` of it.
+    Arrive { radius: f32 },
+    // Circles the target at `radius`.
+    Orbit { radius: f32, clockwise: bool },
+    // Heads for the point `distance` away from the target, `angle` radians
+    // around from the direction it's moving in.
+    Flank { angle: f32, distance: f32 },
+    // Away from other enemies closer than `radius`, harder the closer they are.
+    Separation { radius: f32 },
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub struct Weighted {
+    pub behaviour: Behaviour,
+    pub weight: f32,
+}
+
+// Blends its behaviours by weight. Enemies without one seek their target.
+#[derive(Component, Clone)]
+pub struct Steering(pub Vec<Weighted>);
+
+// Where every enemy was at the start of the step, bucketed by grid cell.
+#[derive(Resource, Default)]
+pub struct Crowd {
+    cells: HashMap<IVec2, Vec<(Entity, Vec2)>>,
+}
+
+impl Crowd {
+    fn cell(position: Vec2) -> IVec2 {
+        (position / CELL_SIZE).floor().as_ivec2()
+    }
+
+    fn neighbours(
+        &self,
+        position: Vec2,
+        radius: f32,
+    ) -> impl Iterator<Item = &(Entity, Vec2)> + '_ {
+        let reach = (radius / CELL_SIZE).ceil() as i32;
+        let center = Self::cell(position);
+        (-reach..=reach)
+            .flat_map(move |x| (-reach..=reach).map(move |y| center + IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+    }
+}
+
+impl Steering {
+    // Desired direction of travel for `entity` at `position`, heading for a
+    // target at `target` moving at `target_velocity`.
+    pub fn direction(
+        &self,
+        entity: Entity,
+        position: Vec2,
+        target: Vec2,
+        target_velocity: Vec2,
+        crowd: &Crowd,
+    ) -> Vec2 {
+        self.0
+            .iter()
+            .map(|weighted| {
+                weighted.weight
+                    * behave(
+                        weighted.behaviour,
+                        entity,
+                        position,
+                        target,
+                        target_velocity,
+                        crowd,
+                    )
+            })
+            .sum::<Vec2>()
+            .clamp_length_max(1.0)
+    }
+}
+
+fn behave(
+    behaviour: Behaviour,
+    entity: Entity,
+    position: Vec2,
+    target: Vec2,
+    target_velocity: Vec2,
+    crowd: &Crowd,
+) -> Vec2 {
+    let to_target = target - position;
+    let distance = to_target.length();
+
+    match behaviour {
+        Behaviour::Seek => to_target.normalize_or_zero(),
+        Behaviour::Arrive { radius } => {
+            to_target.normalize_or_zero() * (distance / radius).min(1.0)
+        }
+        Behaviour::Orbit { radius, clockwise } => {
+            let inward = to_target.normalize_or_zero();
+            let tangent = if clockwise {
+                -inward.perp()
+            } else {
+                inward.perp()
+            };
+            let correction = ((distance - radius) / radius).clamp(-1.0, 1.0);
+            (tangent + inward * correction).normalize_or_zero()
+        }
+        Behaviour::Flank { angle, distance } => {
+            // Targets standing still are flanked from wherever the enemy already is.
+            let facing = target_velocity
+                .try_normalize()
+                .unwrap_or_else(|| -to_target.normalize_or_zero());
+            let flank = target + Vec2::from_angle(angle).rotate(facing) * distance;
+            (flank - position).normalize_or_zero()
+        }
+        Behaviour::Separation { radius } => crowd
+            .neighbours(position, radius)
+            .filter(|(other, _)| *other != entity)
+            .filter_map(|(_, other)| {
+                let away = position - *other;
+                let distance = away.length();
+                if distance >= radius || distance <= 0.0 {
+                    return None;
+                }
+                Some(away / distance * (1.0 - distance / radius))
+            })
+            .sum::<Vec2>()
+            .clamp_length_max(1.0),
+    }
+}
+
+#[derive(SystemSet, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Plugin;
+
+impl Plugin {
+    fn index_crowd(
+        mut crowd: ResMut<Crowd>,
+        q_enemy: Query<(Entity, &GlobalTransform), With<Enemy>>,
+    ) {
+        for cell in crowd.cells.values_mut() {
+            cell.clear();
+        }
+        for (entity, transform) in &q_enemy {
+            let position = transform.translation().truncate();
+            crowd
+                .cells
+                .entry(Crowd::cell(position))
+                .or_default()
+                .push((entity, position));
+        }
+    }
+}
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Crowd>()
+            .add_system(
+                Self::index_crowd
+                    .in_set(Self)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+                schedule.configure_set(Self.run_if(in_state(GameState::InGame)));
+            });
+    }
+}
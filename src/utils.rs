@@ -1,7 +1,7 @@
-use bevy::{prelude::*, window::PrimaryWindow};
+use bevy::{prelude::*, transform::TransformSystem, window::PrimaryWindow};
 use bevy_rapier2d::prelude::*;
 
-use crate::{GameState, MainCamera};
+use crate::{FixedSet, GameState, MainCamera};
 
 #[derive(Resource)]
 pub struct MousePosition(pub Vec3);
@@ -17,6 +17,15 @@ impl Lifespan {
 
 pub struct PlaySound(pub String);
 
+// Where a moving entity was at the last two fixed steps. Rendering places it
+// between them by how far the next step is, and the simulated position is put
+// back before each step.
+#[derive(Component)]
+struct Interpolated {
+    previous: Vec3,
+    current: Vec3,
+}
+
 pub struct Plugin;
 
 impl Plugin {
@@ -37,10 +46,10 @@ impl Plugin {
     fn update_lifespan(
         mut cmd: Commands,
         mut q_lifespan: Query<(Entity, &mut Lifespan)>,
-        time: Res<Time>,
+        time: Res<FixedTime>,
     ) {
         for (entity, mut lifespan) in &mut q_lifespan {
-            lifespan.0.tick(time.delta());
+            lifespan.0.tick(time.period);
             if lifespan.0.finished() {
                 cmd.entity(entity).despawn_recursive();
             }
@@ -48,11 +57,45 @@ impl Plugin {
     }
     fn velocity_abuse(
         mut vel_query: Query<(&mut Transform, &Velocity), Without<RigidBody>>,
-        time: Res<Time>,
+        time: Res<FixedTime>,
     ) {
+        let dt = time.period.as_secs_f32();
         for (mut transform, vel) in &mut vel_query {
-            transform.translation += time.delta_seconds() * vel.linvel.extend(0.0);
-            transform.rotation *= Quat::from_rotation_z(time.delta_seconds() * vel.angvel);
+            transform.translation += dt * vel.linvel.extend(0.0);
+            transform.rotation *= Quat::from_rotation_z(dt * vel.angvel);
+        }
+    }
+
+    fn restore_interpolated(mut q_interpolated: Query<(&mut Transform, &mut Interpolated)>) {
+        for (mut transform, mut interpolated) in &mut q_interpolated {
+            transform.translation = interpolated.current;
+            interpolated.previous = interpolated.current;
+        }
+    }
+
+    fn record_interpolated(
+        mut cmd: Commands,
+        mut q_interpolated: Query<(&Transform, &mut Interpolated)>,
+        q_new: Query<(Entity, &Transform), (With<Velocity>, Without<Interpolated>)>,
+    ) {
+        for (transform, mut interpolated) in &mut q_interpolated {
+            interpolated.current = transform.translation;
+        }
+        for (entity, transform) in &q_new {
+            cmd.entity(entity).insert(Interpolated {
+                previous: transform.translation,
+                current: transform.translation,
+            });
+        }
+    }
+
+    fn interpolate(
+        mut q_interpolated: Query<(&mut Transform, &Interpolated)>,
+        time: Res<FixedTime>,
+    ) {
+        let alpha = time.accumulated().as_secs_f32() / time.period.as_secs_f32();
+        for (mut transform, interpolated) in &mut q_interpolated {
+            transform.translation = interpolated.previous.lerp(interpolated.current, alpha);
         }
     }
 
@@ -100,7 +143,30 @@ impl bevy::app::Plugin for Plugin {
             .add_system(Self::play_sound)
             .add_system(Self::pause_on_lost_focus)
             .add_system(Self::update_mouse_position.run_if(in_state(GameState::InGame)))
-            .add_system(Self::update_lifespan.run_if(in_state(GameState::InGame)))
-            .add_system(Self::velocity_abuse.run_if(in_state(GameState::InGame)));
+            .add_system(
+                Self::update_lifespan
+                    .run_if(in_state(GameState::InGame))
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                Self::velocity_abuse
+                    .run_if(in_state(GameState::InGame))
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                Self::restore_interpolated
+                    .in_base_set(FixedSet::Restore)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                Self::record_interpolated
+                    .in_base_set(FixedSet::Record)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                Self::interpolate
+                    .in_base_set(CoreSet::PostUpdate)
+                    .before(TransformSystem::TransformPropagate),
+            );
     }
 }
@@ -1,59 +1,741 @@
 use bevy::prelude::*;
 use bevy_prototype_lyon::prelude::*;
 use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
 
 use crate::{
     enemy::{Enemy, EnemyHitbox, Hitstun},
+    force::WaveImpulseEvent,
+    geometry::{broadphase, circle_intersection},
     health::HealthChangeEvent,
+    player::Player,
+    profile::{Echo, EchoPattern, WaveProfile},
     utils::{Lifespan, PlaySound},
     GameState,
 };
 
-#[derive(Clone, Copy)]
+#[derive(Deserialize, Clone, Copy, PartialEq)]
 pub enum WaveKind {
     Positive,
     Negative,
 }
 
 #[derive(Clone, Copy, PartialEq)]
+pub enum Faction {
+    Player,
+    Enemy,
+}
+
+/// How two overlapping wave fronts combine.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
 pub enum InterferenceKind {
+    /// The fronts are out of phase and cancel. The only way a player and an enemy
+    /// front interfere.
     Destructive,
+    /// Two player fronts that are not out of phase, where the one with the higher
+    /// amplitude at that point is positive. The other may be of either kind.
     Positive,
+    /// Two player fronts that are not out of phase, where the one with the higher
+    /// amplitude at that point is negative. The other may be of either kind.
     Negative,
 }
 
+/// Sensor spawned along the path an interference point swept during a frame.
 #[derive(Component)]
 pub struct WaveInterference {
     kind: InterferenceKind,
     direction: Vec2,
     strength: f32,
+    waves: [Entity; 2],
 }
 
+impl WaveInterference {
+    pub fn kind(&self) -> InterferenceKind {
+        self.kind
+    }
+
+    /// Unit direction the interference point is travelling in.
+    pub fn direction(&self) -> Vec2 {
+        self.direction
+    }
+
+    /// From 0 to 1, falling off with the distance both fronts have travelled and
+    /// with how far out of phase they are.
+    pub fn strength(&self) -> f32 {
+        self.strength
+    }
+
+    /// The two waves whose fronts produced the interference.
+    pub fn waves(&self) -> [Entity; 2] {
+        self.waves
+    }
+}
+
+/// Sent once per fixed step for every point where two wave fronts intersect,
+/// except where:
+/// - both fronts belong to enemies, which never interfere, or a player and an
+///   enemy front meet in phase, since enemy fronts can only be cancelled;
+/// - three or more fronts meet, where a single `MultiInterferenceEvent` is sent
+///   instead of the events for each pair.
 pub struct WaveInterferenceEvent {
     kind: InterferenceKind,
     position: Vec2,
+    previous_position: Vec2,
     direction: Vec2,
     strength: f32,
+    waves: [Entity; 2],
+}
+
+impl WaveInterferenceEvent {
+    pub fn kind(&self) -> InterferenceKind {
+        self.kind
+    }
+
+    /// Where the fronts intersect this frame.
+    pub fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    /// Where the same intersection point was at the start of the frame.
+    pub fn previous_position(&self) -> Vec2 {
+        self.previous_position
+    }
+
+    /// Unit direction the intersection point is travelling in.
+    pub fn direction(&self) -> Vec2 {
+        self.direction
+    }
+
+    /// From 0 to 1, falling off with the distance both fronts have travelled and
+    /// with how far out of phase they are.
+    pub fn strength(&self) -> f32 {
+        self.strength
+    }
+
+    /// The two waves whose fronts intersect.
+    pub fn waves(&self) -> [Entity; 2] {
+        self.waves
+    }
+}
+
+/// Sent instead of the individual pair events when the fronts of three or more
+/// waves meet at nearly the same point and reinforce each other. Sent only once
+/// for each set of waves, although the pair events stay suppressed for as long
+/// as their fronts keep meeting.
+pub struct MultiInterferenceEvent {
+    kind: InterferenceKind,
+    position: Vec2,
+    strength: f32,
+    waves: Vec<Entity>,
+}
+
+impl MultiInterferenceEvent {
+    /// Either `Positive` or `Negative`, whichever kind of wave contributes most.
+    pub fn kind(&self) -> InterferenceKind {
+        self.kind
+    }
+
+    pub fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    /// From 0 to 1, the combined amplitude of every contributing front relative to
+    /// the same number of fully in-phase fronts at full amplitude.
+    pub fn strength(&self) -> f32 {
+        self.strength
+    }
+
+    /// Every wave whose front passes through the point.
+    pub fn waves(&self) -> &[Entity] {
+        &self.waves
+    }
+}
+
+/// Sent when an interference sensor starts touching another entity.
+pub struct InterferenceHitEvent {
+    interference: Entity,
+    target: Entity,
+    kind: InterferenceKind,
+    strength: f32,
+    waves: [Entity; 2],
+}
+
+impl InterferenceHitEvent {
+    /// The short-lived `WaveInterference` sensor that made contact.
+    pub fn interference(&self) -> Entity {
+        self.interference
+    }
+
+    /// The entity the sensor touched.
+    pub fn target(&self) -> Entity {
+        self.target
+    }
+
+    pub fn kind(&self) -> InterferenceKind {
+        self.kind
+    }
+
+    pub fn strength(&self) -> f32 {
+        self.strength
+    }
+
+    /// The two waves whose fronts produced the interference.
+    pub fn waves(&self) -> [Entity; 2] {
+        self.waves
+    }
 }
 
 #[derive(Component)]
 pub struct NoEffect;
 
+// Coherence (cosine of the phase difference) at or below which two fronts cancel.
+const DESTRUCTIVE_COHERENCE: f32 = 0.0;
+// Interference points closer together than this are treated as one.
+const COINCIDENCE_TOLERANCE: f32 = 12.0;
+// Minimum coherence (length of the summed phasors over the summed amplitudes)
+// for three or more fronts to count as reinforcing each other.
+const COINCIDENCE_COHERENCE: f32 = 0.7;
+const MULTI_RADIUS: f32 = 30.0;
+const MULTI_DAMAGE: f32 = 15.0;
+
+// Fraction of the wave speed beyond which a source's velocity stops adding to
+// the Doppler shift.
+const MAX_SOURCE_SPEED: f32 = 0.5;
+
 impl WaveKind {
-    fn color(&self) -> Color {
+    pub fn color(&self) -> Color {
         match self {
             WaveKind::Positive => Color::RED,
             WaveKind::Negative => Color::BLUE,
         }
     }
+
+    pub fn inverted(&self) -> WaveKind {
+        match self {
+            WaveKind::Positive => WaveKind::Negative,
+            WaveKind::Negative => WaveKind::Positive,
+        }
+    }
+
+    fn phase_offset(&self) -> f32 {
+        match self {
+            WaveKind::Positive => 0.0,
+            WaveKind::Negative => std::f32::consts::PI,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum WaveShape {
+    Ring,
+    Arc { direction: f32, width: f32 },
+    // A straight front perpendicular to `direction`, `radius` away from the
+    // origin. Lines are not reflected or refracted.
+    Line { direction: f32, half_length: f32 },
+}
+
+// Part of a wave reflected off a wall segment, emitted from the mirror image of
+// the original source. The segment is relative to that mirrored origin.
+#[derive(Clone, Copy)]
+pub struct Reflection {
+    source: Entity,
+    start: Vec2,
+    end: Vec2,
+}
+
+impl Reflection {
+    // Only the part of the front that has bounced back through the segment exists.
+    fn covers(&self, offset: Vec2) -> bool {
+        let wall = self.end - self.start;
+        let past_wall = wall.perp_dot(offset - self.start) * wall.perp_dot(-self.start) <= 0.0;
+
+        let span = self.start.perp_dot(self.end);
+        let within_span =
+            self.start.perp_dot(offset) * span >= 0.0 && offset.perp_dot(self.end) * span >= 0.0;
+
+        past_wall && within_span
+    }
+
+    fn angles(&self) -> (f32, f32) {
+        let start = self.start.y.atan2(self.start.x);
+        let sweep = self.end.y.atan2(self.end.x) - start;
+        let sweep =
+            (sweep + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+        (start, sweep)
+    }
+}
+
+const REFRACTION_SEGMENTS: usize = 32;
+const CANCEL_SECTIONS: u32 = u64::BITS;
+
+// How far each section of a front has fallen behind (or run ahead of) the
+// nominal radius after crossing media. Sections are spread evenly by angle and
+// interpolated between, so the front is traced as a chain of arcs.
+#[derive(Clone, Copy, Default)]
+pub struct Refraction {
+    lag: [f32; REFRACTION_SEGMENTS],
+}
+
+impl Refraction {
+    fn section_angle(i: usize) -> f32 {
+        i as f32 * std::f32::consts::TAU / REFRACTION_SEGMENTS as f32
+    }
+
+    fn lag_towards(&self, offset: Vec2) -> f32 {
+        let angle = offset.y.atan2(offset.x).rem_euclid(std::f32::consts::TAU);
+        let position = angle / std::f32::consts::TAU * REFRACTION_SEGMENTS as f32;
+        let i = position as usize % REFRACTION_SEGMENTS;
+        let next = (i + 1) % REFRACTION_SEGMENTS;
+        let fract = position.fract();
+        self.lag[i] * (1.0 - fract) + self.lag[next] * fract
+    }
+
+    fn min_lag(&self) -> f32 {
+        self.lag.iter().copied().fold(0.0, f32::min)
+    }
 }
 
 #[derive(Component, Clone)]
 pub struct Wave {
     pub kind: WaveKind,
+    pub faction: Faction,
+    pub shape: WaveShape,
     pub radius: f32,
-    pub max_radius: f32,
-    pub speed: f32,
+    pub prev_radius: f32,
+    pub phase: f32,
+    pub reflection: Option<Reflection>,
+    pub refraction: Option<Refraction>,
+    // One bit per section of the front that destructive interference has
+    // cancelled out. Sections are angular, or spread along the length of a line.
+    pub cancelled: u64,
+    // Velocity of whatever emitted the front, at the moment it was emitted.
+    pub source_velocity: Vec2,
+    pub profile: WaveProfile,
+    pub profile_handle: Handle<WaveProfile>,
+}
+
+// Speeds up movement along the outward normal of nearby fronts by up to `along`,
+// and slows movement against it by up to `against`.
+#[derive(Component, Deserialize, Clone, Copy)]
+pub struct WaveRider {
+    pub along: f32,
+    pub against: f32,
+}
+
+impl WaveRider {
+    pub fn speed_modifier<'a>(
+        &self,
+        position: Vec2,
+        direction: Vec2,
+        waves: impl Iterator<Item = (&'a Wave, &'a GlobalTransform)>,
+    ) -> f32 {
+        let direction = direction.normalize_or_zero();
+        let mut modifier = 1.0;
+
+        for (wave, transform) in waves {
+            let offset = position - transform.translation().truncate();
+            if !wave.touches(offset) {
+                continue;
+            }
+            let alignment = direction.dot(wave.normal_at(offset));
+            let scale = if alignment >= 0.0 {
+                self.along
+            } else {
+                self.against
+            };
+            modifier += scale * alignment * wave.amplitude();
+        }
+
+        modifier.clamp(0.25, 2.0)
+    }
+}
+
+// Reflects waves off the edges of a box centered on the entity.
+#[derive(Component)]
+pub struct Reflector {
+    pub half_extents: Vec2,
+}
+
+// Scales the speed of any part of a front passing through a box centered on the
+// entity.
+#[derive(Component)]
+pub struct Medium {
+    pub half_extents: Vec2,
+    pub speed_factor: f32,
+}
+
+impl Medium {
+    fn local_point(transform: &GlobalTransform, point: Vec2) -> Vec2 {
+        transform
+            .affine()
+            .inverse()
+            .transform_point3(point.extend(0.0))
+            .truncate()
+    }
+
+    fn contains(&self, transform: &GlobalTransform, point: Vec2) -> bool {
+        let local = Self::local_point(transform, point);
+        local.x.abs() <= self.half_extents.x && local.y.abs() <= self.half_extents.y
+    }
+
+    fn distance(&self, transform: &GlobalTransform, point: Vec2) -> f32 {
+        let local = Self::local_point(transform, point);
+        local.distance(local.clamp(-self.half_extents, self.half_extents))
+    }
+}
+
+impl Reflector {
+    fn edges(&self, transform: &GlobalTransform) -> [(Vec2, Vec2); 4] {
+        let corner = |x: f32, y: f32| {
+            transform
+                .transform_point((self.half_extents * Vec2::new(x, y)).extend(0.0))
+                .truncate()
+        };
+        let corners = [
+            corner(-1.0, -1.0),
+            corner(1.0, -1.0),
+            corner(1.0, 1.0),
+            corner(-1.0, 1.0),
+        ];
+        [
+            (corners[0], corners[1]),
+            (corners[1], corners[2]),
+            (corners[2], corners[3]),
+            (corners[3], corners[0]),
+        ]
+    }
+}
+
+impl Wave {
+    pub fn new(
+        kind: WaveKind,
+        faction: Faction,
+        shape: WaveShape,
+        profile_handle: Handle<WaveProfile>,
+        profile: WaveProfile,
+    ) -> Self {
+        Wave {
+            kind,
+            faction,
+            shape,
+            radius: 0.0,
+            prev_radius: 0.0,
+            phase: 0.0,
+            reflection: None,
+            refraction: None,
+            cancelled: 0,
+            source_velocity: Vec2::ZERO,
+            profile,
+            profile_handle,
+        }
+    }
+
+    // Phase of the front in the direction of `offset` from the wave's origin,
+    // accumulated over the distance travelled so far at the local wavelength.
+    pub fn front_phase(&self, offset: Vec2) -> f32 {
+        self.kind.phase_offset()
+            + self.phase
+            + std::f32::consts::TAU * self.radius * self.doppler_factor(offset)
+                / self.profile.wavelength
+    }
+
+    // Phase the front will have when it passes through `offset` from the wave's
+    // origin, assuming it does not refract on the way.
+    pub fn phase_at(&self, offset: Vec2) -> f32 {
+        self.kind.phase_offset()
+            + self.phase
+            + std::f32::consts::TAU * self.travel_distance(offset) * self.doppler_factor(offset)
+                / self.profile.wavelength
+    }
+
+    pub fn amplitude(&self) -> f32 {
+        1.0 - (self.radius / self.profile.max_radius).powi(2)
+    }
+
+    pub fn amplitude_towards(&self, offset: Vec2) -> f32 {
+        self.amplitude() * self.doppler_factor(offset)
+    }
+
+    // How much the wavelength is compressed in the direction of `offset` by the
+    // motion of the source: above 1 ahead of it, below 1 behind it.
+    pub fn doppler_factor(&self, offset: Vec2) -> f32 {
+        let speed = self.profile.speed;
+        let velocity = self
+            .source_velocity
+            .clamp_length_max(speed * MAX_SOURCE_SPEED);
+        speed / (speed - velocity.dot(self.normal_at(offset)))
+    }
+
+    pub fn color(&self) -> Color {
+        match (self.faction, self.kind) {
+            (Faction::Player, kind) => kind.color(),
+            (Faction::Enemy, WaveKind::Positive) => Color::ORANGE,
+            (Faction::Enemy, WaveKind::Negative) => Color::PURPLE,
+        }
+    }
+
+    // Returns the kind of interference between two fronts where they meet, at
+    // `offset` and `other_offset` from their origins, and its coherence, from -1
+    // (fully destructive) to 1 (fully constructive). Enemy fronts never reinforce
+    // anything, they can only be cancelled by the player's.
+    pub fn interference_with(
+        &self,
+        offset: Vec2,
+        other: &Wave,
+        other_offset: Vec2,
+    ) -> Option<(InterferenceKind, f32)> {
+        let coherence = f32::cos(self.front_phase(offset) - other.front_phase(other_offset));
+
+        match (self.faction, other.faction) {
+            (Faction::Player, Faction::Player) => {}
+            (Faction::Enemy, Faction::Enemy) => return None,
+            _ if coherence <= DESTRUCTIVE_COHERENCE => {
+                return Some((InterferenceKind::Destructive, coherence))
+            }
+            _ => return None,
+        }
+
+        if coherence <= DESTRUCTIVE_COHERENCE {
+            return Some((InterferenceKind::Destructive, coherence));
+        }
+
+        let dominant = if self.amplitude_towards(offset) >= other.amplitude_towards(other_offset) {
+            self.kind
+        } else {
+            other.kind
+        };
+
+        let kind = match dominant {
+            WaveKind::Positive => InterferenceKind::Positive,
+            WaveKind::Negative => InterferenceKind::Negative,
+        };
+
+        Some((kind, coherence))
+    }
+
+    // Moves the front on by a step of `dt` seconds, remembering where it was.
+    fn advance(&mut self, dt: f32) {
+        self.prev_radius = self.radius;
+        self.radius += self.profile.speed * dt;
+    }
+
+    // Radius of the front in the direction of `offset` from the wave's origin.
+    pub fn radius_towards(&self, offset: Vec2) -> f32 {
+        self.radius - self.refraction.map_or(0.0, |r| r.lag_towards(offset))
+    }
+
+    pub fn prev_radius_towards(&self, offset: Vec2) -> f32 {
+        self.prev_radius - self.refraction.map_or(0.0, |r| r.lag_towards(offset))
+    }
+
+    // Furthest any part of the front has travelled from the origin.
+    pub fn reach(&self) -> f32 {
+        if let WaveShape::Line { half_length, .. } = self.shape {
+            return self.radius.hypot(half_length);
+        }
+        self.radius - self.refraction.map_or(0.0, |r| r.min_lag())
+    }
+
+    // Where a line's front crosses its axis at `radius`, relative to the origin,
+    // and the unit direction along the front.
+    fn line_front(&self, radius: f32) -> Option<(Vec2, Vec2)> {
+        let WaveShape::Line { direction, .. } = self.shape else { return None };
+        let direction = Vec2::from_angle(direction);
+        Some((direction * radius, direction.perp()))
+    }
+
+    // Direction the front is travelling in at `offset` from the wave's origin.
+    pub fn normal_at(&self, offset: Vec2) -> Vec2 {
+        match self.shape {
+            WaveShape::Line { direction, .. } => Vec2::from_angle(direction),
+            _ => offset.normalize_or_zero(),
+        }
+    }
+
+    pub fn front_width(&self) -> f32 {
+        self.profile.line_width
+            + self.profile.line_growth * (self.radius / self.profile.max_radius).powi(2)
+    }
+
+    // Whether a point at `offset` from the wave's origin lies within the drawn front.
+    pub fn touches(&self, offset: Vec2) -> bool {
+        self.covers(offset)
+            && f32::abs(self.travel_distance(offset) - self.radius_towards(offset))
+                < self.front_width()
+    }
+
+    // How far the front has to travel to reach `offset` from the wave's origin.
+    pub fn travel_distance(&self, offset: Vec2) -> f32 {
+        match self.shape {
+            WaveShape::Line { direction, .. } => offset.dot(Vec2::from_angle(direction)),
+            _ => offset.length(),
+        }
+    }
+
+    pub fn is_reflection_of(&self, entity: Entity) -> bool {
+        matches!(self.reflection, Some(reflection) if reflection.source == entity)
+    }
+
+    fn angle_section(offset: Vec2) -> u32 {
+        let angle = offset.y.atan2(offset.x).rem_euclid(std::f32::consts::TAU);
+        (angle / std::f32::consts::TAU * CANCEL_SECTIONS as f32) as u32 % CANCEL_SECTIONS
+    }
+
+    fn section(&self, offset: Vec2) -> u32 {
+        match self.shape {
+            WaveShape::Line {
+                direction,
+                half_length,
+            } => {
+                let along = offset.dot(Vec2::from_angle(direction).perp());
+                let fraction = (along / half_length + 1.0) / 2.0;
+                ((fraction * CANCEL_SECTIONS as f32) as u32).min(CANCEL_SECTIONS - 1)
+            }
+            _ => Self::angle_section(offset),
+        }
+    }
+
+    fn section_direction(section: u32) -> Vec2 {
+        Vec2::from_angle((section as f32 + 0.5) / CANCEL_SECTIONS as f32 * std::f32::consts::TAU)
+    }
+
+    pub fn is_cancelled(&self, offset: Vec2) -> bool {
+        self.cancelled & (1 << self.section(offset)) != 0
+    }
+
+    // Cancels every section between two points on the front, along the shorter
+    // arc for curved fronts.
+    pub fn cancel_between(&mut self, from: Vec2, to: Vec2) {
+        let from = self.section(from);
+        let to = self.section(to);
+
+        if let WaveShape::Line { .. } = self.shape {
+            for section in from.min(to)..=from.max(to) {
+                self.cancelled |= 1 << section;
+            }
+            return;
+        }
+
+        let forward = (to + CANCEL_SECTIONS - from) % CANCEL_SECTIONS;
+
+        let (start, count) = if forward <= CANCEL_SECTIONS / 2 {
+            (from, forward)
+        } else {
+            (to, CANCEL_SECTIONS - forward)
+        };
+
+        for i in 0..=count {
+            self.cancelled |= 1 << ((start + i) % CANCEL_SECTIONS);
+        }
+    }
+
+    // Whether the front passes through the point at `offset` from the wave's origin.
+    pub fn covers(&self, offset: Vec2) -> bool {
+        if self.is_cancelled(offset) {
+            return false;
+        }
+
+        if let Some(reflection) = self.reflection {
+            if !reflection.covers(offset) {
+                return false;
+            }
+        }
+
+        match self.shape {
+            WaveShape::Ring => true,
+            WaveShape::Arc { direction, width } => arc_contains(direction, width, offset),
+            WaveShape::Line {
+                direction,
+                half_length,
+            } => offset.dot(Vec2::from_angle(direction).perp()).abs() <= half_length,
+        }
+    }
+
+    // Whether any part of the front, cancelled or not, points at the wall from
+    // `start` to `end`, both relative to the wave's origin.
+    fn faces(&self, start: Vec2, end: Vec2) -> bool {
+        let WaveShape::Arc { direction, width } = self.shape else { return true };
+        let span = start.perp_dot(end);
+        let within_span =
+            |v: Vec2| start.perp_dot(v) * span >= 0.0 && v.perp_dot(end) * span >= 0.0;
+
+        within_span(Vec2::from_angle(direction - width / 2.0))
+            || within_span(Vec2::from_angle(direction + width / 2.0))
+            || arc_contains(direction, width, start)
+    }
+
+    pub fn build_path(&self) -> Path {
+        if let WaveShape::Line {
+            direction,
+            half_length,
+        } = self.shape
+        {
+            return self.build_line_path(Vec2::from_angle(direction), half_length);
+        }
+
+        let (start, sweep) = match (self.reflection, self.shape) {
+            (Some(reflection), _) => reflection.angles(),
+            (None, WaveShape::Ring) if self.refraction.is_none() && self.cancelled == 0 => {
+                return GeometryBuilder::build_as(&shapes::Circle {
+                    radius: self.radius,
+                    center: Vec2::ZERO,
+                })
+            }
+            (None, WaveShape::Ring) => (0.0, std::f32::consts::TAU),
+            (None, WaveShape::Arc { direction, width }) => (direction - width / 2.0, width),
+            (None, WaveShape::Line { .. }) => unreachable!(),
+        };
+
+        let segments = if self.refraction.is_some() || self.cancelled != 0 {
+            128
+        } else {
+            32
+        };
+        let fract = sweep / segments as f32;
+        let mut path = PathBuilder::new();
+        let mut drawing = false;
+
+        for i in 0..=segments {
+            let direction = Vec2::from_angle(start + i as f32 * fract);
+            let point = direction * self.radius_towards(direction);
+            if !self.covers(point) {
+                drawing = false;
+            } else if drawing {
+                path.line_to(point);
+            } else {
+                path.move_to(point);
+                drawing = true;
+            }
+        }
+        path.build()
+    }
+
+    fn build_line_path(&self, direction: Vec2, half_length: f32) -> Path {
+        let center = direction * self.radius;
+        let along = direction.perp();
+        let mut path = PathBuilder::new();
+
+        if self.cancelled == 0 {
+            path.move_to(center - along * half_length);
+            path.line_to(center + along * half_length);
+            return path.build();
+        }
+
+        // Draw each section that is still intact as its own segment.
+        let section_length = 2.0 * half_length / CANCEL_SECTIONS as f32;
+        let mut drawing = false;
+        for section in 0..=CANCEL_SECTIONS {
+            let point = center + along * (section as f32 * section_length - half_length);
+            let intact = section < CANCEL_SECTIONS && self.cancelled & (1 << section) == 0;
+            if drawing {
+                path.line_to(point);
+            } else if intact {
+                path.move_to(point);
+            }
+            drawing = intact;
+        }
+        path.build()
+    }
 }
 
 #[derive(Bundle)]
@@ -79,39 +761,174 @@ impl DelayedWave {
     }
 }
 
+// Keeps emitting fronts from wherever `source` currently is, alternating
+// between kinds every `interval`, so they bunch up ahead of a moving source
+// and spread out behind it.
+#[derive(Component)]
+pub struct DopplerSource {
+    source: Entity,
+    wave: Wave,
+    interval_timer: Timer,
+    remaining: u32,
+}
+
+impl DopplerSource {
+    pub fn new(source: Entity, wave: Wave, interval: f32, fronts: u32) -> Self {
+        let mut interval_timer = Timer::from_seconds(interval, TimerMode::Repeating);
+        // Emit the first front on the next tick.
+        interval_timer.set_elapsed(interval_timer.duration());
+        DopplerSource {
+            source,
+            wave,
+            interval_timer,
+            remaining: fronts,
+        }
+    }
+}
+
 #[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
 pub struct Plugin;
 
 impl Plugin {
     fn update_wave(
         mut cmd: Commands,
-        mut q_wave: Query<(Entity, &mut Wave, &mut Path, &mut Stroke)>,
-        time: Res<Time>,
+        mut q_wave: Query<(Entity, &mut Wave, &GlobalTransform)>,
+        q_medium: Query<(&Medium, &GlobalTransform)>,
+        time: Res<FixedTime>,
     ) {
-        for (entity, mut wave, mut path, mut stroke) in &mut q_wave {
-            wave.radius += wave.speed * time.delta_seconds();
-            if wave.radius >= wave.max_radius {
+        for (entity, mut wave, transform) in &mut q_wave {
+            wave.advance(time.period.as_secs_f32());
+            if wave.radius >= wave.profile.max_radius {
+                if wave.reflection.is_none() {
+                    spawn_echoes(&mut cmd, &wave, transform.translation());
+                }
                 cmd.entity(entity).despawn_recursive();
                 continue;
             }
-            *path = GeometryBuilder::build_as(&shapes::Circle {
-                radius: wave.radius,
-                center: Vec2::ZERO,
-            });
-            stroke.options.line_width = 2.0 + 30.0 * (wave.radius / wave.max_radius).powi(2);
+
+            let origin = transform.translation().truncate();
+            if wave.refraction.is_none()
+                && !matches!(wave.shape, WaveShape::Line { .. })
+                && q_medium.iter().any(|(medium, medium_transform)| {
+                    medium.distance(medium_transform, origin) <= wave.radius
+                })
+            {
+                wave.refraction = Some(Refraction::default());
+            }
+
+            let growth = wave.profile.speed * time.period.as_secs_f32();
+            let wave = &mut *wave;
+            if let Some(refraction) = &mut wave.refraction {
+                for (i, lag) in refraction.lag.iter_mut().enumerate() {
+                    let direction = Vec2::from_angle(Refraction::section_angle(i));
+                    let point = origin + direction * (wave.radius - *lag);
+                    let speed_factor: f32 = q_medium
+                        .iter()
+                        .filter(|(medium, medium_transform)| {
+                            medium.contains(medium_transform, point)
+                        })
+                        .map(|(medium, _)| medium.speed_factor)
+                        .product();
+                    *lag += (1.0 - speed_factor) * growth;
+                }
+            }
+        }
+    }
+
+    // Draws each front between its radius at the last two fixed steps, by how far
+    // the next step is.
+    fn draw_waves(mut q_wave: Query<(&Wave, &mut Path, &mut Stroke)>, time: Res<FixedTime>) {
+        let alpha = time.accumulated().as_secs_f32() / time.period.as_secs_f32();
+        for (wave, mut path, mut stroke) in &mut q_wave {
+            let drawn = Wave {
+                radius: wave.prev_radius + (wave.radius - wave.prev_radius) * alpha,
+                ..wave.clone()
+            };
+            *path = drawn.build_path();
+            stroke.options.line_width = drawn.front_width();
             stroke
                 .color
-                .set_a(1.0 - (wave.radius / wave.max_radius).powi(2));
+                .set_a(1.0 - (drawn.radius / drawn.profile.max_radius).powf(drawn.profile.fade));
         }
     }
+    fn reflect_waves(
+        mut cmd: Commands,
+        q_wave: Query<(Entity, &Wave, &GlobalTransform)>,
+        q_reflector: Query<(&Reflector, &GlobalTransform)>,
+    ) {
+        for (entity, wave, transform) in &q_wave {
+            if wave.reflection.is_some() || matches!(wave.shape, WaveShape::Line { .. }) {
+                continue;
+            }
+            let origin = transform.translation().truncate();
+
+            for (reflector, reflector_transform) in &q_reflector {
+                for (start, end) in reflector.edges(reflector_transform) {
+                    let wall = end - start;
+                    let t = ((origin - start).dot(wall) / wall.length_squared()).clamp(0.0, 1.0);
+                    let closest = start + wall * t;
+                    let distance = origin.distance(closest);
+
+                    if distance <= wave.prev_radius_towards(closest - origin)
+                        || distance > wave.radius_towards(closest - origin)
+                        || !wave.faces(start - origin, end - origin)
+                    {
+                        continue;
+                    }
+
+                    let normal = wall.perp().normalize();
+                    let mirror = |v: Vec2| v - 2.0 * v.dot(normal) * normal;
+                    let virtual_origin = start + mirror(origin - start);
+
+                    let mut reflected = wave.clone();
+                    if let WaveShape::Arc { direction, width } = wave.shape {
+                        let direction = mirror(Vec2::from_angle(direction));
+                        reflected.shape = WaveShape::Arc {
+                            direction: direction.y.atan2(direction.x),
+                            width,
+                        };
+                    }
+                    reflected.refraction = None;
+                    reflected.source_velocity = mirror(wave.source_velocity);
+                    reflected.cancelled = 0;
+                    for section in 0..CANCEL_SECTIONS {
+                        if wave.cancelled & (1 << section) != 0 {
+                            let direction = mirror(Wave::section_direction(section));
+                            reflected.cancelled |= 1 << Wave::angle_section(direction);
+                        }
+                    }
+                    reflected.reflection = Some(Reflection {
+                        source: entity,
+                        start: start - virtual_origin,
+                        end: end - virtual_origin,
+                    });
+
+                    cmd.spawn((
+                        WaveBundle {
+                            shape_bundle: ShapeBundle {
+                                path: reflected.build_path(),
+                                transform: Transform::from_translation(
+                                    virtual_origin.extend(transform.translation().z),
+                                ),
+                                ..default()
+                            },
+                            wave: reflected,
+                        },
+                        Stroke::new(wave.color(), 2.0),
+                    ));
+                }
+            }
+        }
+    }
+
     fn update_delayed_wave(
         mut cmd: Commands,
         mut q_delayed_wave: Query<(Entity, &mut DelayedWave)>,
         mut ev_sound: EventWriter<PlaySound>,
-        time: Res<Time>,
+        time: Res<FixedTime>,
     ) {
         for (entity, mut delayed_wave) in &mut q_delayed_wave {
-            delayed_wave.delay_timer.tick(time.delta());
+            delayed_wave.delay_timer.tick(time.period);
 
             if delayed_wave.delay_timer.finished() {
                 cmd.spawn((
@@ -122,7 +939,7 @@ impl Plugin {
                             ..default()
                         },
                     },
-                    Stroke::new(delayed_wave.wave.kind.color(), 2.0),
+                    Stroke::new(delayed_wave.wave.color(), 2.0),
                 ));
                 cmd.entity(entity).despawn_recursive();
                 match delayed_wave.wave.kind {
@@ -133,90 +950,227 @@ impl Plugin {
         }
     }
 
+    fn update_doppler_source(
+        mut cmd: Commands,
+        mut q_doppler: Query<(Entity, &mut DopplerSource)>,
+        q_source: Query<(&GlobalTransform, Option<&Velocity>)>,
+        mut ev_sound: EventWriter<PlaySound>,
+        time: Res<FixedTime>,
+    ) {
+        for (entity, mut doppler) in &mut q_doppler {
+            let Ok((transform, velocity)) = q_source.get(doppler.source) else {
+                cmd.entity(entity).despawn_recursive();
+                continue;
+            };
+
+            doppler.interval_timer.tick(time.period);
+            if !doppler.interval_timer.just_finished() {
+                continue;
+            }
+
+            let mut wave = doppler.wave.clone();
+            wave.source_velocity = velocity.map_or(Vec2::ZERO, |velocity| velocity.linvel);
+            let color = wave.color();
+            cmd.spawn((
+                WaveBundle {
+                    shape_bundle: ShapeBundle {
+                        path: wave.build_path(),
+                        transform: Transform::from_translation(transform.translation()),
+                        ..default()
+                    },
+                    wave,
+                },
+                Stroke::new(color, 2.0),
+            ));
+            match doppler.wave.kind {
+                WaveKind::Positive => ev_sound.send(PlaySound("ding.ogg".to_owned())),
+                WaveKind::Negative => ev_sound.send(PlaySound("dong.ogg".to_owned())),
+            }
+
+            doppler.remaining -= 1;
+            if doppler.remaining == 0 {
+                cmd.entity(entity).despawn_recursive();
+            }
+            doppler.wave.kind = doppler.wave.kind.inverted();
+        }
+    }
+
     fn detect_interference(
         mut ev_interference: EventWriter<WaveInterferenceEvent>,
-        q_wave: Query<(&Wave, &GlobalTransform)>,
+        mut ev_multi: EventWriter<MultiInterferenceEvent>,
+        mut q_wave: Query<(Entity, &mut Wave, &GlobalTransform)>,
+        mut bounds: Local<Vec<(Entity, Vec2, f32)>>,
+        mut pairs: Local<Vec<(Entity, Entity)>>,
+        mut cancellations: Local<Vec<(Entity, Vec2, Vec2)>>,
+        mut intersections: Local<Vec<(Vec2, Vec2)>>,
+        mut points: Local<Vec<(WaveInterferenceEvent, bool)>>,
+        mut coincided: Local<Vec<Vec<Entity>>>,
     ) {
-        for [(wave1, transform1), (wave2, transform2)] in q_wave.iter_combinations() {
+        coincided.retain(|waves| waves.iter().all(|entity| q_wave.contains(*entity)));
+
+        bounds.clear();
+        bounds.extend(q_wave.iter().map(|(entity, wave, transform)| {
+            (entity, transform.translation().truncate(), wave.reach())
+        }));
+        broadphase(&mut bounds, &mut pairs);
+
+        for (entity1, entity2) in pairs.iter() {
+            let Ok([(_, wave1, transform1), (_, wave2, transform2)]) =
+                q_wave.get_many([*entity1, *entity2]) else { continue };
+            if wave1.is_reflection_of(*entity2) || wave2.is_reflection_of(*entity1) {
+                continue;
+            }
             let pos1 = transform1.translation().truncate();
             let pos2 = transform2.translation().truncate();
-            let distance = pos1.distance(pos2);
-            if distance == 0.0
-                || distance > wave1.radius + wave2.radius
-                || distance < f32::abs(wave1.radius - wave2.radius)
-            {
-                continue;
+
+            intersections.clear();
+            match (wave1.shape, wave2.shape) {
+                (WaveShape::Line { .. }, WaveShape::Line { .. }) => {
+                    line_intersections(pos1, wave1, pos2, wave2, &mut intersections)
+                }
+                (WaveShape::Line { .. }, _) => {
+                    line_ring_intersections(pos1, wave1, pos2, wave2, &mut intersections)
+                }
+                (_, WaveShape::Line { .. }) => {
+                    line_ring_intersections(pos2, wave2, pos1, wave1, &mut intersections)
+                }
+                _ => ring_intersections(pos1, wave1, pos2, wave2, &mut intersections),
             }
 
-            let a =
-                (wave1.radius.powi(2) - wave2.radius.powi(2) + distance.powi(2)) / (2.0 * distance);
-            let h = f32::sqrt(wave1.radius.powi(2) - a.powi(2));
+            for &(previous_position, position) in intersections.iter() {
+                let (offset1, offset2) = (position - pos1, position - pos2);
+                if !wave1.covers(offset1) || !wave2.covers(offset2) {
+                    continue;
+                }
+                let interference = wave1.interference_with(offset1, wave2, offset2);
+                let Some((interference_kind, coherence)) = interference else { continue };
+                let strength = f32::min(
+                    wave1.amplitude_towards(offset1),
+                    wave2.amplitude_towards(offset2),
+                ) * coherence.abs();
 
-            let center = pos1 + a * (pos2 - pos1) / distance;
+                if interference_kind == InterferenceKind::Destructive {
+                    cancellations.push((*entity1, previous_position - pos1, offset1));
+                    cancellations.push((*entity2, previous_position - pos2, offset2));
+                }
+                let interference = WaveInterferenceEvent {
+                    kind: interference_kind,
+                    position,
+                    previous_position,
+                    direction: (position - previous_position).normalize_or_zero(),
+                    strength: strength.min(1.0),
+                    waves: [*entity1, *entity2],
+                };
+                points.push((interference, false));
+            }
+        }
 
-            let intersect_offset = Vec2::new(
-                h * (pos2.y - pos1.y) / distance,
-                -h * (pos2.x - pos1.x) / distance,
-            );
+        // Points where three or more fronts coincide replace the pair events there.
+        for i in 0..points.len() {
+            if points[i].1 {
+                continue;
+            }
+            let seed = points[i].0.position;
+            let mut waves: Vec<Entity> = points[i..]
+                .iter()
+                .filter(|(point, merged)| {
+                    !merged && point.position.distance(seed) <= COINCIDENCE_TOLERANCE
+                })
+                .flat_map(|(point, _)| point.waves)
+                .collect();
+            waves.sort_unstable();
+            waves.dedup();
+            if waves.len() < 3 {
+                continue;
+            }
 
-            let interference_kind = match (&wave1.kind, &wave2.kind) {
-                (WaveKind::Positive, WaveKind::Positive) => InterferenceKind::Positive,
-                (WaveKind::Positive, WaveKind::Negative)
-                | (WaveKind::Negative, WaveKind::Positive) => InterferenceKind::Destructive,
-                (WaveKind::Negative, WaveKind::Negative) => InterferenceKind::Negative,
-            };
+            let contributions = waves.iter().filter_map(|entity| {
+                let (_, wave, transform) = q_wave.get(*entity).ok()?;
+                Some((wave, seed - transform.translation().truncate()))
+            });
+            let Some((kind, strength)) = coincidence(contributions) else { continue };
 
-            let prev_radius1 = wave1.radius - 0.1;
-            let prev_radius2 = wave2.radius - 0.1;
+            for (point, merged) in points[i..].iter_mut() {
+                if point.position.distance(seed) <= COINCIDENCE_TOLERANCE {
+                    *merged = true;
+                }
+            }
+            // The fronts stay close for several steps after meeting, but only burst
+            // once.
+            if coincided.contains(&waves) {
+                continue;
+            }
+            coincided.push(waves.clone());
+            ev_multi.send(MultiInterferenceEvent {
+                kind,
+                position: seed,
+                strength,
+                waves,
+            });
+        }
+        for (interference, merged) in points.drain(..) {
+            if !merged {
+                ev_interference.send(interference);
+            }
+        }
 
-            let prev_a =
-                (prev_radius1.powi(2) - prev_radius2.powi(2) + distance.powi(2)) / (2.0 * distance);
-            let prev_h = f32::sqrt(prev_radius1.powi(2) - prev_a.powi(2));
-            let prev_center = pos1 + prev_a * (pos2 - pos1) / distance;
+        for (entity, from, to) in cancellations.drain(..) {
+            if let Ok((_, mut wave, _)) = q_wave.get_mut(entity) {
+                wave.cancel_between(from, to);
+            }
+        }
+    }
 
-            let prev_intersect_offset = Vec2::new(
-                prev_h * (pos2.y - pos1.y) / distance,
-                -prev_h * (pos2.x - pos1.x) / distance,
-            );
+    fn multi_interference(
+        mut cmd: Commands,
+        mut q_enemy: Query<(Entity, &GlobalTransform, &mut Hitstun), With<Enemy>>,
+        q_projectile: Query<(Entity, &GlobalTransform), (With<EnemyHitbox>, Without<Enemy>)>,
+        mut ev_multi: EventReader<MultiInterferenceEvent>,
+        mut ev_health: EventWriter<HealthChangeEvent>,
+        mut ev_sound: EventWriter<PlaySound>,
+    ) {
+        for multi in &mut ev_multi {
+            let radius = MULTI_RADIUS * multi.waves.len() as f32;
 
-            if intersect_offset.length() <= 5.0 {
-                ev_interference.send(WaveInterferenceEvent {
-                    kind: interference_kind,
-                    position: center,
-                    direction: center - prev_center,
-                    strength: 1.0
-                        - f32::min(
-                            (wave1.radius / wave1.max_radius).powi(2),
-                            (wave2.radius / wave2.max_radius).powi(2),
-                        ),
-                });
-            } else {
-                ev_interference.send(WaveInterferenceEvent {
-                    kind: interference_kind,
-                    position: center + intersect_offset,
-                    direction: ((center + intersect_offset)
-                        - (prev_center + prev_intersect_offset))
-                        .normalize(),
-
-                    strength: 1.0
-                        - f32::min(
-                            (wave1.radius / wave1.max_radius).powi(2),
-                            (wave2.radius / wave2.max_radius).powi(2),
-                        ),
-                });
-                ev_interference.send(WaveInterferenceEvent {
-                    kind: interference_kind,
-                    position: center - intersect_offset,
-                    direction: ((center - intersect_offset)
-                        - (prev_center - prev_intersect_offset))
-                        .normalize(),
-                    strength: 1.0
-                        - f32::min(
-                            (wave1.radius / wave1.max_radius).powi(2),
-                            (wave2.radius / wave2.max_radius).powi(2),
-                        ),
-                });
+            match multi.kind {
+                InterferenceKind::Positive => {
+                    for (enemy, transform, mut hitstun) in &mut q_enemy {
+                        if transform.translation().truncate().distance(multi.position) <= radius {
+                            ev_health.send(HealthChangeEvent {
+                                target: enemy,
+                                amount: -MULTI_DAMAGE * multi.waves.len() as f32 * multi.strength,
+                            });
+                            hitstun.set(0.5);
+                        }
+                    }
+                }
+                InterferenceKind::Negative => {
+                    for (projectile, transform) in &q_projectile {
+                        if transform.translation().truncate().distance(multi.position) <= radius {
+                            cmd.entity(projectile).despawn_recursive();
+                        }
+                    }
+                }
+                InterferenceKind::Destructive => {}
             }
+
+            let color = match multi.kind {
+                InterferenceKind::Negative => WaveKind::Negative.color(),
+                _ => WaveKind::Positive.color(),
+            };
+            cmd.spawn((
+                ShapeBundle {
+                    path: GeometryBuilder::build_as(&shapes::Circle {
+                        radius,
+                        center: Vec2::ZERO,
+                    }),
+                    transform: Transform::from_translation(multi.position.extend(0.02)),
+                    ..default()
+                },
+                Stroke::new(color, 4.0),
+                Lifespan::new(0.2),
+            ));
+            ev_sound.send(PlaySound("ding.ogg".to_owned()));
         }
     }
 
@@ -227,58 +1181,87 @@ impl Plugin {
                 InterferenceKind::Positive => 15.0,
                 InterferenceKind::Negative => 25.0,
             };
+            let radius = 2.0 + interference_size * (1.0 - interference.strength);
+            let midpoint = (interference.previous_position + interference.position) / 2.0;
+
+            // The sensor covers the whole path the intersection point swept this step,
+            // so it only has to live until the next physics step.
             cmd.spawn((
                 SpatialBundle {
-                    transform: Transform::from_translation(
-                        (interference.position + interference.direction * 2.0).extend(0.01),
-                    ),
+                    transform: Transform::from_translation(midpoint.extend(0.01)),
                     ..default()
                 },
                 WaveInterference {
                     kind: interference.kind,
                     direction: interference.direction,
                     strength: interference.strength,
+                    waves: interference.waves,
                 },
-                Lifespan::new(0.05),
-                Collider::ball(2.0 + interference_size * (1.0 - interference.strength)),
+                Lifespan::new(0.0),
+                Collider::capsule(
+                    interference.previous_position - midpoint,
+                    interference.position - midpoint,
+                    radius,
+                ),
                 Sensor,
                 ActiveEvents::COLLISION_EVENTS,
             ));
         }
     }
 
+    fn interference_hits(
+        q_interference: Query<&WaveInterference>,
+        mut ev_collisions: EventReader<CollisionEvent>,
+        mut ev_hit: EventWriter<InterferenceHitEvent>,
+    ) {
+        for collision in &mut ev_collisions {
+            let CollisionEvent::Started(e1, e2, _) = collision else { continue };
+
+            for (interference_entity, target) in [(e1, e2), (e2, e1)] {
+                if let Ok(interference) = q_interference.get(*interference_entity) {
+                    ev_hit.send(InterferenceHitEvent {
+                        interference: *interference_entity,
+                        target: *target,
+                        kind: interference.kind,
+                        strength: interference.strength,
+                        waves: interference.waves,
+                    });
+                }
+            }
+        }
+    }
+
     fn enemy_interaction(
         q_wave: Query<(&Wave, &GlobalTransform)>,
         q_enemy: Query<(Entity, &GlobalTransform), (With<Enemy>, Without<NoEffect>)>,
-        mut q_projectile: Query<
-            (&GlobalTransform, &mut Velocity),
-            (With<EnemyHitbox>, Without<Enemy>),
-        >,
+        q_player: Query<(Entity, &GlobalTransform), (With<Player>, Without<NoEffect>)>,
         mut ev_health: EventWriter<HealthChangeEvent>,
-        time: Res<Time>,
+        time: Res<FixedTime>,
     ) {
         for (wave, wave_transform) in &q_wave {
             let wave_origin = wave_transform.translation().truncate();
-            match wave.kind {
-                WaveKind::Positive => {
+            match (wave.faction, wave.kind) {
+                (Faction::Player, WaveKind::Positive) => {
                     for (enemy_entity, enemy_transform) in &q_enemy {
                         let enemy_pos = enemy_transform.translation().truncate();
-                        let offset = f32::abs(enemy_pos.distance(wave_origin) - wave.radius);
-                        if offset < 2.0 + 30.0 * (wave.radius / wave.max_radius).powi(2) {
+                        if wave.touches(enemy_pos - wave_origin) {
                             ev_health.send(HealthChangeEvent {
                                 target: enemy_entity,
-                                amount: -10.0 * time.delta_seconds(),
+                                amount: -wave.profile.damage * time.period.as_secs_f32(),
                             });
                         }
                     }
                 }
-                WaveKind::Negative => {
-                    for (enemy_transform, mut vel) in &mut q_projectile {
-                        let enemy_pos = enemy_transform.translation().truncate();
-                        let offset = f32::abs(enemy_pos.distance(wave_origin) - wave.radius);
-                        if offset < 2.0 + 30.0 * (wave.radius / wave.max_radius).powi(2) {
-                            vel.linvel +=
-                                (enemy_pos - wave_origin).normalize() * 10.0 * time.delta_seconds();
+                // Negative fronts only push, through `WaveSusceptible`.
+                (Faction::Player, WaveKind::Negative) => {}
+                (Faction::Enemy, _) => {
+                    for (player_entity, player_transform) in &q_player {
+                        let player_pos = player_transform.translation().truncate();
+                        if wave.touches(player_pos - wave_origin) {
+                            ev_health.send(HealthChangeEvent {
+                                target: player_entity,
+                                amount: -wave.profile.damage * time.period.as_secs_f32(),
+                            });
                         }
                     }
                 }
@@ -291,6 +1274,7 @@ impl Plugin {
         mut q_enemy: Query<(&mut Hitstun, &mut Velocity), (With<Enemy>, Without<NoEffect>)>,
         mut ev_collisions: EventReader<CollisionEvent>,
         mut ev_health: EventWriter<HealthChangeEvent>,
+        mut ev_impulse: EventWriter<WaveImpulseEvent>,
     ) {
         for collision in &mut ev_collisions {
             let mut enemy_hitstun;
@@ -331,9 +1315,14 @@ impl Plugin {
                         amount: -10.0 * (0.5 + interference.strength / 2.0),
                     });
                 }
+                // Stunned enemies stop before being knocked back.
                 enemy_hitstun.set(0.25);
-                enemy_velocity.linvel =
-                    interference.direction * 50.0 * (0.5 + interference.strength / 2.0);
+                enemy_velocity.linvel = Vec2::ZERO;
+                ev_impulse.send(WaveImpulseEvent {
+                    target: *enemy_entity,
+                    impulse: interference.direction * 50.0 * (0.5 + interference.strength / 2.0),
+                    kind: WaveKind::Positive,
+                });
             }
         }
     }
@@ -379,7 +1368,7 @@ impl Plugin {
     fn destructive_interference(
         mut cmd: Commands,
         q_interference: Query<&WaveInterference>,
-        q_enemy: Query<Entity, With<Enemy>>,
+        q_enemy: Query<Entity, Or<(With<Enemy>, With<Player>)>>,
         mut ev_collisions: EventReader<CollisionEvent>,
     ) {
         for collision in &mut ev_collisions {
@@ -436,34 +1425,501 @@ impl Plugin {
     }
 }
 
+// Spawns the weaker rings a wave with an echo leaves behind when it expires.
+fn spawn_echoes(cmd: &mut Commands, wave: &Wave, origin: Vec3) {
+    let Some(echo) = wave.profile.echo else { return };
+    if echo.depth == 0 {
+        return;
+    }
+
+    let mut profile = wave.profile.clone();
+    profile.max_radius *= echo.falloff;
+    profile.damage *= echo.falloff;
+    profile.echo = Some(Echo {
+        depth: echo.depth - 1,
+        ..echo
+    });
+
+    let mut spawn = |kind: WaveKind, offset: Vec2| {
+        // Echoes keep their own scaled profile rather than following the file.
+        let child = Wave::new(
+            kind,
+            wave.faction,
+            WaveShape::Ring,
+            Handle::default(),
+            profile.clone(),
+        );
+        let color = child.color();
+        cmd.spawn((
+            WaveBundle {
+                shape_bundle: ShapeBundle {
+                    path: child.build_path(),
+                    transform: Transform::from_translation(origin + offset.extend(0.0)),
+                    ..default()
+                },
+                wave: child,
+            },
+            Stroke::new(color, 2.0),
+        ));
+    };
+
+    match (echo.pattern, wave.shape) {
+        (EchoPattern::Cardinal, WaveShape::Line { direction, .. }) => {
+            spawn(wave.kind, Vec2::from_angle(direction) * wave.radius)
+        }
+        (EchoPattern::Cardinal, _) => {
+            for direction in [Vec2::X, Vec2::Y, Vec2::NEG_X, Vec2::NEG_Y] {
+                let offset = direction * wave.radius_towards(direction);
+                if wave.covers(offset) {
+                    spawn(wave.kind, offset);
+                }
+            }
+        }
+        (EchoPattern::Inverted, _) => spawn(wave.kind.inverted(), Vec2::ZERO),
+    }
+}
+
+// Kind and strength of the interference between several fronts meeting at a
+// point, each paired with the point's offset from that wave's origin. Only
+// player fronts that reinforce each other combine.
+fn coincidence<'a>(
+    contributions: impl Iterator<Item = (&'a Wave, Vec2)>,
+) -> Option<(InterferenceKind, f32)> {
+    let mut phasor = Vec2::ZERO;
+    let mut total = 0.0;
+    let mut positive = 0.0;
+    let mut count = 0;
+
+    for (wave, offset) in contributions {
+        if wave.faction != Faction::Player {
+            return None;
+        }
+        let amplitude = wave.amplitude_towards(offset);
+        phasor += Vec2::from_angle(wave.front_phase(offset)) * amplitude;
+        total += amplitude;
+        if wave.kind == WaveKind::Positive {
+            positive += amplitude;
+        }
+        count += 1;
+    }
+
+    if total <= 0.0 || phasor.length() / total < COINCIDENCE_COHERENCE {
+        return None;
+    }
+
+    let kind = if positive >= total - positive {
+        InterferenceKind::Positive
+    } else {
+        InterferenceKind::Negative
+    };
+    Some((kind, (phasor.length() / count as f32).min(1.0)))
+}
+
+// Whether `offset` points within an arc `width` wide centred on `direction`.
+fn arc_contains(direction: f32, width: f32, offset: Vec2) -> bool {
+    let angle = offset.y.atan2(offset.x) - direction;
+    let angle =
+        (angle + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+    angle.abs() <= width / 2.0
+}
+
+// Points where two curved fronts intersect, paired with where each point was at
+// the start of the frame.
+fn ring_intersections(
+    pos1: Vec2,
+    wave1: &Wave,
+    pos2: Vec2,
+    wave2: &Wave,
+    intersections: &mut Vec<(Vec2, Vec2)>,
+) {
+    let towards1 = pos2 - pos1;
+    let towards2 = pos1 - pos2;
+
+    let Some((center, intersect_offset)) = circle_intersection(
+        pos1,
+        wave1.radius_towards(towards1),
+        pos2,
+        wave2.radius_towards(towards2),
+    ) else { return };
+    let prev_intersection = circle_intersection(
+        pos1,
+        wave1.prev_radius_towards(towards1),
+        pos2,
+        wave2.prev_radius_towards(towards2),
+    );
+    let contact = || {
+        first_contact(
+            pos1,
+            wave1.prev_radius_towards(towards1),
+            wave1.radius_towards(towards1),
+            pos2,
+            wave2.prev_radius_towards(towards2),
+            wave2.radius_towards(towards2),
+        )
+    };
+    let refracted = wave1.refraction.is_some() || wave2.refraction.is_some();
+
+    let sides: &[f32] = if intersect_offset.length() <= 5.0 {
+        &[0.0]
+    } else {
+        &[1.0, -1.0]
+    };
+    for &side in sides {
+        let mut previous_position = match prev_intersection {
+            Some((prev_center, prev_offset)) => prev_center + side * prev_offset,
+            None => contact(),
+        };
+        let mut position = center + side * intersect_offset;
+
+        if refracted {
+            let refined = refine_intersection(pos1, wave1, pos2, wave2, position, side, false);
+            let Some(refined) = refined else { continue };
+            position = refined;
+            previous_position =
+                refine_intersection(pos1, wave1, pos2, wave2, previous_position, side, true)
+                    .unwrap_or(previous_position);
+        }
+        intersections.push((previous_position, position));
+    }
+}
+
+// Points where a line's front crosses a curved front. A crossing that did not
+// exist at the start of the frame starts where the ring first touched the line.
+fn line_ring_intersections(
+    line_pos: Vec2,
+    line: &Wave,
+    ring_pos: Vec2,
+    ring: &Wave,
+    intersections: &mut Vec<(Vec2, Vec2)>,
+) {
+    let Some((front, along)) = line.line_front(line.radius) else { return };
+    let Some((prev_front, _)) = line.line_front(line.prev_radius) else { return };
+
+    let front = line_pos + front;
+    let prev_front = line_pos + prev_front;
+    let towards_ring = |front: Vec2| front + along * (ring_pos - front).dot(along) - ring_pos;
+
+    let Some((foot, offset)) = line_circle_intersection(
+        front,
+        along,
+        ring_pos,
+        ring.radius_towards(towards_ring(front)),
+    ) else { return };
+    let prev_intersection = line_circle_intersection(
+        prev_front,
+        along,
+        ring_pos,
+        ring.prev_radius_towards(towards_ring(prev_front)),
+    );
+
+    let sides: &[f32] = if offset.length() <= 5.0 {
+        &[0.0]
+    } else {
+        &[1.0, -1.0]
+    };
+    for &side in sides {
+        let previous_position = match prev_intersection {
+            Some((prev_foot, prev_offset)) => prev_foot + side * prev_offset,
+            None => foot,
+        };
+        intersections.push((previous_position, foot + side * offset));
+    }
+}
+
+// The single point where two non-parallel line fronts cross.
+fn line_intersections(
+    pos1: Vec2,
+    wave1: &Wave,
+    pos2: Vec2,
+    wave2: &Wave,
+    intersections: &mut Vec<(Vec2, Vec2)>,
+) {
+    let crossing = |radius1: f32, radius2: f32| {
+        let (front1, along1) = wave1.line_front(radius1)?;
+        let (front2, along2) = wave2.line_front(radius2)?;
+        line_intersection(pos1 + front1, along1, pos2 + front2, along2)
+    };
+
+    let Some(position) = crossing(wave1.radius, wave2.radius) else { return };
+    let previous_position = crossing(wave1.prev_radius, wave2.prev_radius).unwrap_or(position);
+    intersections.push((previous_position, position));
+}
+
+// Intersection of the line through `point` along the unit vector `along` with a
+// circle, as the point on the line closest to the center and the offset from it
+// to either intersection point.
+fn line_circle_intersection(
+    point: Vec2,
+    along: Vec2,
+    center: Vec2,
+    radius: f32,
+) -> Option<(Vec2, Vec2)> {
+    let foot = point + along * (center - point).dot(along);
+    let distance = foot.distance(center);
+    if distance > radius {
+        return None;
+    }
+
+    Some((foot, along * f32::sqrt(radius.powi(2) - distance.powi(2))))
+}
+
+fn line_intersection(point1: Vec2, along1: Vec2, point2: Vec2, along2: Vec2) -> Option<Vec2> {
+    let denominator = along1.perp_dot(along2);
+    if denominator.abs() < 1e-4 {
+        return None;
+    }
+
+    Some(point1 + along1 * (point2 - point1).perp_dot(along2) / denominator)
+}
+
+// Moves an intersection point found from the nominal radii onto the local radii
+// of refracted fronts, re-solving with the radii towards the previous estimate.
+fn refine_intersection(
+    pos1: Vec2,
+    wave1: &Wave,
+    pos2: Vec2,
+    wave2: &Wave,
+    mut point: Vec2,
+    side: f32,
+    previous: bool,
+) -> Option<Vec2> {
+    for _ in 0..3 {
+        let (radius1, radius2) = if previous {
+            (
+                wave1.prev_radius_towards(point - pos1),
+                wave2.prev_radius_towards(point - pos2),
+            )
+        } else {
+            (
+                wave1.radius_towards(point - pos1),
+                wave2.radius_towards(point - pos2),
+            )
+        };
+        let (center, offset) = circle_intersection(pos1, radius1, pos2, radius2)?;
+        point = center + side * offset;
+    }
+    Some(point)
+}
+
+// Point where two fronts that did not intersect at the start of the frame first
+// touched, assuming both radii grew linearly over the frame.
+fn first_contact(
+    pos1: Vec2,
+    prev_radius1: f32,
+    radius1: f32,
+    pos2: Vec2,
+    prev_radius2: f32,
+    radius2: f32,
+) -> Vec2 {
+    let distance = pos1.distance(pos2);
+    let direction = (pos2 - pos1) / distance;
+    let growth1 = radius1 - prev_radius1;
+    let growth2 = radius2 - prev_radius2;
+
+    let clamp = |t: f32| {
+        if t.is_finite() {
+            t.clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    };
+
+    if prev_radius1 + prev_radius2 < distance {
+        let t = clamp((distance - prev_radius1 - prev_radius2) / (growth1 + growth2));
+        pos1 + direction * (prev_radius1 + growth1 * t)
+    } else if prev_radius1 > prev_radius2 {
+        let t = clamp((prev_radius1 - prev_radius2 - distance) / (growth2 - growth1));
+        pos1 + direction * (prev_radius1 + growth1 * t)
+    } else {
+        let t = clamp((prev_radius2 - prev_radius1 - distance) / (growth1 - growth2));
+        pos2 - direction * (prev_radius2 + growth2 * t)
+    }
+}
+
 impl bevy::app::Plugin for Plugin {
     fn build(&self, app: &mut App) {
         app.add_event::<WaveInterferenceEvent>()
-            .add_system(Self::update_wave.in_set(Self))
-            .add_system(Self::update_delayed_wave.in_set(Self))
-            .add_system(Self::detect_interference.in_set(Self))
+            .add_event::<MultiInterferenceEvent>()
+            .add_event::<InterferenceHitEvent>()
+            .add_system(
+                Self::update_wave
+                    .in_set(Self)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(Self::draw_waves.run_if(in_state(GameState::InGame)))
+            .add_system(
+                Self::update_delayed_wave
+                    .in_set(Self)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                Self::update_doppler_source
+                    .in_set(Self)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                Self::reflect_waves
+                    .after(Self::update_wave)
+                    .in_set(Self)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                Self::detect_interference
+                    .after(Self::update_wave)
+                    .in_set(Self)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
             .add_system(
                 Self::interfere
                     .after(Self::detect_interference)
-                    .in_set(Self),
+                    .in_set(Self)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                Self::multi_interference
+                    .after(Self::detect_interference)
+                    .in_set(Self)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                Self::interference_hits
+                    .in_set(Self)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                Self::destructive_interference
+                    .in_set(Self)
+                    .in_schedule(CoreSchedule::FixedUpdate),
             )
-            .add_system(Self::destructive_interference.in_set(Self))
             .add_system(
                 Self::positive_interference
                     .after(Self::destructive_interference)
-                    .in_set(Self),
+                    .in_set(Self)
+                    .in_schedule(CoreSchedule::FixedUpdate),
             )
             .add_system(
                 Self::negative_interference
                     .after(Self::destructive_interference)
-                    .in_set(Self),
+                    .in_set(Self)
+                    .in_schedule(CoreSchedule::FixedUpdate),
             )
             .add_system(
                 Self::enemy_interaction
                     .after(Self::destructive_interference)
-                    .in_set(Self),
+                    .in_set(Self)
+                    .in_schedule(CoreSchedule::FixedUpdate),
             )
-            .add_system(Self::cleanup_no_effect.in_set(Plugin));
-        app.configure_set(Self.run_if(in_state(GameState::InGame)));
+            .add_system(
+                Self::cleanup_no_effect
+                    .in_set(Self)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+                schedule.configure_set(Self.run_if(in_state(GameState::InGame)));
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATES: [f32; 3] = [30.0, 60.0, 240.0];
+
+    fn ring() -> Wave {
+        Wave::new(
+            WaveKind::Positive,
+            Faction::Player,
+            WaveShape::Ring,
+            Handle::default(),
+            WaveProfile::default(),
+        )
+    }
+
+    fn distance_to_segment(point: Vec2, start: Vec2, end: Vec2) -> f32 {
+        let along = end - start;
+        let t = if along.length_squared() > 0.0 {
+            ((point - start).dot(along) / along.length_squared()).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        point.distance(start + along * t)
+    }
+
+    // Grows two rings 301 apart for three seconds at `rate` steps per second and
+    // returns the segments swept by the intersection point above their axis.
+    fn swept_path(rate: f32) -> Vec<(Vec2, Vec2)> {
+        let (pos1, pos2) = (Vec2::new(-150.5, 0.0), Vec2::new(150.5, 0.0));
+        let (mut wave1, mut wave2) = (ring(), ring());
+        let mut intersections = Vec::new();
+        let mut path = Vec::new();
+
+        for _ in 0..(3.0 * rate).round() as u32 {
+            wave1.advance(1.0 / rate);
+            wave2.advance(1.0 / rate);
+            intersections.clear();
+            ring_intersections(pos1, &wave1, pos2, &wave2, &mut intersections);
+            path.extend(
+                intersections
+                    .iter()
+                    .filter(|(_, position)| position.y > 0.0),
+            );
+        }
+        path
+    }
+
+    #[test]
+    fn swept_path_is_independent_of_step_rate() {
+        let end = Vec2::new(0.0, f32::sqrt(300.0f32.powi(2) - 150.5f32.powi(2)));
+
+        for rate in RATES {
+            let path = swept_path(rate);
+            let (first, last) = (path[0], path[path.len() - 1]);
+
+            assert!(
+                first.0.distance(Vec2::ZERO) < 1e-3,
+                "path starts at {} at {rate} Hz",
+                first.0
+            );
+            assert!(
+                last.1.distance(end) < 0.05,
+                "path ends at {} at {rate} Hz",
+                last.1
+            );
+            for window in path.windows(2) {
+                assert!(
+                    window[0].1.distance(window[1].0) < 1e-3,
+                    "gap between {} and {} at {rate} Hz",
+                    window[0].1,
+                    window[1].0
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn probe_is_swept_at_every_step_rate() {
+        let probe = Vec2::new(0.0, 100.0);
+
+        for rate in RATES {
+            let hit = swept_path(rate)
+                .iter()
+                .any(|(start, end)| distance_to_segment(probe, *start, *end) < 1e-2);
+            assert!(hit, "probe missed at {rate} Hz");
+        }
+    }
+
+    #[test]
+    fn first_contact_between_separate_fronts() {
+        let contact = first_contact(Vec2::ZERO, 90.0, 110.0, Vec2::new(200.0, 0.0), 90.0, 110.0);
+        assert!(contact.distance(Vec2::new(100.0, 0.0)) < 1e-3);
+        assert!(circle_intersection(Vec2::ZERO, 90.0, Vec2::new(200.0, 0.0), 90.0).is_none());
+    }
+
+    #[test]
+    fn first_contact_from_inside() {
+        let contact = first_contact(Vec2::ZERO, 100.0, 120.0, Vec2::new(30.0, 0.0), 60.0, 100.0);
+        assert!(contact.distance(Vec2::new(110.0, 0.0)) < 1e-3);
+        assert!(circle_intersection(Vec2::ZERO, 100.0, Vec2::new(30.0, 0.0), 60.0).is_none());
     }
 }